@@ -1,4 +1,6 @@
-use std::{collections::{HashMap, HashSet}, ops::Mul, sync::{Arc, atomic::{AtomicI32, Ordering}}};
+// native_db's Error is large, and every database call returns it.
+#![allow(clippy::result_large_err)]
+use std::{collections::{HashMap, HashSet}, num::NonZeroU32, ops::Mul, sync::{Arc, atomic::{AtomicI32, Ordering}}};
 use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
 use serde::{Deserialize, Serialize};
 use native_db::{db_type::Error, *};
@@ -7,45 +9,2976 @@ use once_cell::sync::Lazy;
 use native_db::{ToInput, ToKey};
 use dioxus::prelude::*;
 
-static BANK_HOLIDAYS: Lazy<BankHolidayChecker> = Lazy::new(|| {
-    BankHolidayChecker::new(vec![
-        BankHoliday::new(NaiveDate::from_ymd_opt(2026, 12, 25).unwrap(), "Christmas".to_string()),
-        BankHoliday::new(NaiveDate::from_ymd_opt(2026, 12, 26).unwrap(), "Boxing Day".to_string()),
-    ])
+static MODELS: Lazy<Models> = Lazy::new(|| {
+    let mut models = Models::new();
+    models.define::<JobV1>().unwrap();
+    models.define::<Job>().unwrap();
+    models.define::<Deduction>().unwrap();
+    models.define::<Shift>().unwrap();
+    models.define::<CustomShiftPaymentType>().unwrap();
+    models.define::<SalaryMultiplier>().unwrap();
+    models.define::<ScheduleOverride>().unwrap();
+    models.define::<BankHoliday>().unwrap();
+    models
 });
+// In the user's data folder, e.g. ~/.local/share/wage_calculator/wages.db on Linux.
+fn get_database_path() -> std::path::PathBuf {
+    let var = |name: &str| std::env::var_os(name).map(std::path::PathBuf::from);
+    let base = if cfg!(target_os = "windows") {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        var("XDG_DATA_HOME").or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+    };
+
+    let folder = base.unwrap_or_default().join("wage_calculator");
+    std::fs::create_dir_all(&folder).expect("Couldn't create the data folder");
+    folder.join("wages.db")
+}
+// Moves records saved by an older version onto the current models.
+fn migrate_models(db: &Database) -> Result<(), Error> {
+    let rw = db.rw_transaction()?;
+    rw.migrate::<Job>()?;
+    rw.commit()
+}
+#[component]
+fn App() -> Element {
+    // Created once and kept on disk, so a running shift is still there after a restart.
+    let db = use_hook(|| {
+        let db = Builder::new().create(&MODELS, get_database_path()).expect("Error!");
+        migrate_models(&db).expect("Error!");
+        Arc::new(db)
+    });
+    let jobs = use_signal(|| Job::load_all(&*db).expect("Error!"));
+    let salary_multipliers = use_signal(|| SalaryMultiplier::load_all(&*db).expect("Error!"));
+    let schedule_overrides = use_signal(|| ScheduleOverride::load_all(&db).expect("Error!"));
+    let bank_holidays = use_signal(|| BankHoliday::load_all(&db).expect("Error!"));
+
+    let id_gen = use_hook(|| Arc::new(IdGenerator::new(&db).expect("Error!")));
+
+    use_context_provider(|| db);
+    use_context_provider(|| id_gen);
+    use_context_provider(|| jobs);
+    use_context_provider(|| salary_multipliers);
+    use_context_provider(|| schedule_overrides);
+    use_context_provider(|| bank_holidays);
+
+    let mut screen = use_signal(|| Screen::Overview);
+
+    rsx!(
+        div { "Wages Calculator App!" }
+        nav {
+            button { onclick: move |_| screen.set(Screen::Overview), "Overview" }
+            button { onclick: move |_| screen.set(Screen::Jobs), "Jobs" }
+            button { onclick: move |_| screen.set(Screen::Calendar), "Calendar" }
+            button { onclick: move |_| screen.set(Screen::Shifts), "Shifts" }
+            button { onclick: move |_| screen.set(Screen::Multipliers), "Multipliers" }
+            button { onclick: move |_| screen.set(Screen::Payslip), "Payslip" }
+        }
+        match screen() {
+            Screen::Overview => rsx!(
+                Dashboard {}
+                WorkingTimeReport {}
+                RoundingReportView {}
+                TipsEntry {}
+                LieuDaysView {}
+                ScheduleOverrideEditor {}
+                BankHolidayEditor {}
+                DraftShifts {}
+                IcsExportView {}
+                IcsImportView {}
+            ),
+            Screen::Jobs => rsx!( JobsScreen {} ),
+            Screen::Calendar => rsx!( CalendarScreen {} ),
+            Screen::Shifts => rsx!( ShiftsScreen {} ),
+            Screen::Multipliers => rsx!( MultipliersScreen {} ),
+            Screen::Payslip => rsx!( PayslipScreen {} ),
+        }
+    )
+
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Screen {
+    Overview,
+    Jobs,
+    Calendar,
+    Shifts,
+    Multipliers,
+    Payslip,
+}
+
+// Month grid of the rota with logged shifts, bank holidays and working time breaches.
+#[component]
+fn CalendarScreen() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let overrides = use_context::<Signal<HashMap<i32, Vec<ScheduleOverride>>>>();
+    let bank_holidays = use_context::<Signal<Vec<BankHoliday>>>();
+
+    let today = chrono::Local::now().date_naive();
+    let mut month_start = use_signal(|| today.with_day(1).unwrap());
+    let mut job_id = use_signal(|| None::<i32>); // None = all jobs
+    let mut selected = use_signal(|| None::<NaiveDate>);
+
+    let first = month_start();
+    let last = first.checked_add_months(chrono::Months::new(1)).unwrap().pred_opt().unwrap();
+
+    let all_jobs = jobs.read();
+    let mut shown: Vec<&Job> = all_jobs.values().filter(|job| job_id().is_none_or(|id| id == job.id)).collect();
+    shown.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut scheduled: HashMap<NaiveDate, Vec<(String, ScheduledShift)>> = HashMap::new();
+    let mut holidays: HashMap<NaiveDate, String> = HashMap::new();
+    for job in &shown {
+        let job_overrides = overrides.read().get(&job.id).cloned().unwrap_or_default();
+        for day in job.get_scheduled_shifts_for_month(first.month(), first.year(), &job_overrides) {
+            scheduled.entry(day.date).or_default().push((job.name.clone(), day));
+        }
+        for holiday in BankHolidayChecker::for_job(job, first.year(), &bank_holidays.read()).holidays {
+            if holiday.date.month() == first.month() {
+                holidays.insert(holiday.date, holiday.name);
+            }
+        }
+    }
+
+    let shifts = Shift::get_shifts_for_period(&db, first, last, job_id()).unwrap_or_default();
+    let breaches = WorkingTimeChecker::check_around(&db, &shown, first, last).unwrap_or_default();
+
+    // Monday first, padded with blanks either side.
+    let mut cells: Vec<Option<NaiveDate>> = vec![None; first.weekday().num_days_from_monday() as usize];
+    cells.extend(first.iter_days().take_while(|date| *date <= last).map(Some));
+    while !cells.len().is_multiple_of(7) { cells.push(None) }
+    let weeks: Vec<Vec<Option<NaiveDate>>> = cells.chunks(7).map(|week| week.to_vec()).collect();
+
+    rsx!(
+        div { class: "calendar-screen",
+            h2 { "Calendar" }
+            div { class: "calendar-controls",
+                button { onclick: move |_| month_start.set(first.checked_sub_months(chrono::Months::new(1)).unwrap()), "Previous" }
+                span { {format!(" {} ", first.format("%B %Y"))} }
+                button { onclick: move |_| month_start.set(first.checked_add_months(chrono::Months::new(1)).unwrap()), "Next" }
+                select {
+                    onchange: move |e| job_id.set(e.value().parse().ok()),
+                    option { value: "", "All jobs" }
+                    for job in all_jobs.values() {
+                        option { value: "{job.id}", selected: job_id() == Some(job.id), "{job.name}" }
+                    }
+                }
+            }
+            table { class: "calendar",
+                tr {
+                    for weekday in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"] {
+                        th { "{weekday}" }
+                    }
+                }
+                for week in weeks {
+                    tr {
+                        for cell in week {
+                            match cell {
+                                None => rsx!( td {} ),
+                                Some(date) => {
+                                    let days = scheduled.get(&date).cloned().unwrap_or_default();
+                                    let is_on = days.iter().any(|(_, day)| day.status == ShiftStatus::ON);
+                                    let holiday = holidays.get(&date).cloned();
+                                    let day_shifts: Vec<&Shift> = shifts.iter().filter(|shift| shift.date == date).collect();
+                                    let breach_count = WorkingTimeChecker::breaches_on(&breaches, date).len();
+                                    let background = if is_on { "#dff0d8" } else { "#f5f5f5" };
+                                    let border = if holiday.is_some() { "2px solid #c9302c" } else if date == today { "2px solid #337ab7" } else { "1px solid #ddd" };
+
+                                    rsx!(
+                                        td {
+                                            key: "{date}",
+                                            style: "background-color: {background}; border: {border}; vertical-align: top; cursor: pointer;",
+                                            onclick: move |_| selected.set(Some(date)),
+                                            strong { "{date.day()}" }
+                                            if let Some(holiday) = holiday {
+                                                div { class: "bank-holiday", "{holiday}" }
+                                            }
+                                            for (job_name, day) in days {
+                                                div { class: if day.status == ShiftStatus::ON { "rota on" } else { "rota off" },
+                                                    if shown.len() > 1 { "{job_name}: " }
+                                                    match day.status {
+                                                        ShiftStatus::ON => format!("ON day {}", day.day_in_cycle),
+                                                        ShiftStatus::OFF => "OFF".to_string(),
+                                                    }
+                                                    if let Some(label) = day.label { " {label}" }
+                                                    if day.is_overridden { " *" }
+                                                }
+                                            }
+                                            for shift in day_shifts {
+                                                div { style: "color: {ShiftType::get_colour(&shift.shift_type)};",
+                                                    {format!("{:?} {}-{}", shift.shift_type, shift.start.format("%H:%M"), shift.finish.format("%H:%M"))}
+                                                    if !shift.is_confirmed { " (draft)" }
+                                                }
+                                            }
+                                            if breach_count > 0 {
+                                                div { class: "breach", style: "color: #c9302c;", "{breach_count} working time issue(s)" }
+                                            }
+                                        }
+                                    )
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some(date) = selected() {
+                DayDetail { date, job_id: job_id() }
+                button { onclick: move |_| selected.set(None), "Close" }
+            }
+        }
+    )
+}
+
+// A day's rota, shifts and estimated pay for each job.
+#[component]
+fn DayDetail(date: NaiveDate, job_id: Option<i32>) -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let multipliers = use_context::<Signal<HashMap<i32, Vec<SalaryMultiplier>>>>();
+    let bank_holidays = use_context::<Signal<Vec<BankHoliday>>>();
+    let overrides = use_context::<Signal<HashMap<i32, Vec<ScheduleOverride>>>>();
+
+    let all_jobs = jobs.read();
+    let shifts = Shift::get_shifts_for_period(&db, date, date, job_id).unwrap_or_default();
+
+    let mut shown: Vec<&Job> = all_jobs.values().filter(|job| job_id.is_none_or(|id| id == job.id)).collect();
+    shown.sort_by(|a, b| a.name.cmp(&b.name));
+    let breaches = WorkingTimeChecker::check_around(&db, &shown, date, date).unwrap_or_default();
+
+    rsx!(
+        div { class: "day-detail",
+            h3 { {date.format("%A %-d %B %Y").to_string()} }
+            for job in shown {
+                {
+                    let job_multipliers = SalaryMultiplier::get_for(job, &multipliers.read()).unwrap_or_default();
+                    let job_overrides = overrides.read().get(&job.id).cloned().unwrap_or_default();
+                    let holidays = BankHolidayChecker::for_job(job, date.year(), &bank_holidays.read());
+                    let scheduled = job.get_scheduled_shift_on(date, &job_overrides);
+                    let job_shifts: Vec<Shift> = shifts.iter().filter(|shift| shift.job_id == job.id).cloned().collect();
+
+                    // Logged shifts are priced as worked, otherwise the rota day is forecast.
+                    let pay = if job_shifts.is_empty() {
+                        scheduled.as_ref().map(|day| ShiftPayment::forecast_for(day, job, &db, job_multipliers.clone(), &holidays))
+                    } else {
+                        Some(job_shifts.iter().try_fold(Vec::new(), |mut all, shift| {
+                            all.extend(ShiftPayment::new_for_shift(shift, job, &db, job_multipliers.clone(), &holidays)?);
+                            Ok(all)
+                        }))
+                    };
+
+                    rsx!(
+                        div { key: "{job.id}",
+                            h4 { "{job.name}" }
+                            if let Some(day) = scheduled {
+                                p {
+                                    match (day.status, day.start, day.finish) {
+                                        (ShiftStatus::ON, Some(start), Some(finish)) => format!("Rota: ON {}-{}", start.format("%H:%M"), finish.format("%H:%M")),
+                                        (ShiftStatus::ON, _, _) => "Rota: ON".to_string(),
+                                        (ShiftStatus::OFF, _, _) => "Rota: OFF".to_string(),
+                                    }
+                                    if day.is_overridden { " (changed)" }
+                                }
+                            }
+                            for shift in job_shifts.iter() {
+                                p { key: "{shift.id}",
+                                    {format!("{:?}: {}-{}, {}", shift.shift_type, shift.start.format("%H:%M"), shift.finish.format("%H:%M"), shift.get_pretty_time_worked())}
+                                }
+                            }
+                            match pay {
+                                Some(Ok(payments)) if !payments.is_empty() => rsx!(
+                                    ul {
+                                        for payment in payments.iter() {
+                                            li { "{payment.payment_type:?}: {format_pence(payment.amount)}" }
+                                        }
+                                    }
+                                    p {
+                                        if job_shifts.is_empty() { "Estimated pay: " } else { "Pay: " }
+                                        "{format_pence(ShiftPayment::get_total(&payments))}"
+                                    }
+                                ),
+                                Some(Err(e)) => rsx!( p { "Couldn't work out the pay: {e}" } ),
+                                _ => rsx!(),
+                            }
+                        }
+                    )
+                }
+            }
+            for breach in breaches.iter() {
+                p { class: "breach", "{breach.describe()}" }
+            }
+        }
+    )
+}
+
+// Clock in and out, and add or edit shifts by hand.
+#[component]
+fn ShiftsScreen() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let id_gen = use_context::<Arc<IdGenerator>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let overrides = use_context::<Signal<HashMap<i32, Vec<ScheduleOverride>>>>();
+
+    let today = chrono::Local::now().date_naive();
+    let mut clock_job_id = use_signal(|| None::<i32>);
+    let mut form = use_signal(|| None::<ShiftForm>);
+    let mut editing = use_signal(|| None::<Shift>); // None = a new shift
+    let mut error = use_signal(|| None::<String>);
+    let mut refresh = use_signal(|| 0);
+
+    let _ = refresh();
+    // Finding the running shift scans every shift, so it's only looked up again on a refresh.
+    let running = use_memo({
+        let db = db.clone();
+        move || {
+            let _ = refresh();
+            Shift::get_running(&db).unwrap_or_default()
+        }
+    });
+    let mut recent = Shift::get_shifts_for_period(&db, today - TimeDelta::days(14), today + TimeDelta::days(7), None).unwrap_or_default();
+    recent.retain(|shift| !shift.is_running);
+    recent.sort_by_key(|shift| std::cmp::Reverse(shift.start));
+
+    let clock_in_db = db.clone();
+    let clock_in_id_gen = id_gen.clone();
+    let clock_in = move |_| {
+        let Some(job) = clock_job_id().and_then(|id| jobs.read().get(&id).cloned()) else {
+            error.set(Some("Choose a job to clock in to.".to_string()));
+            return
+        };
+        let job_overrides = overrides.read().get(&job.id).cloned().unwrap_or_default();
+
+        match Shift::clock_in(&clock_in_db, &clock_in_id_gen, &job, chrono::Local::now().naive_local(), &job_overrides) {
+            Ok(_) => error.set(None),
+            Err(e) => error.set(Some(format!("Couldn't clock in: {e}"))),
+        }
+        refresh += 1;
+    };
+
+    let clock_out_db = db.clone();
+    let clock_out = move |_| {
+        let Some(shift) = Shift::get_running(&clock_out_db).ok().flatten() else { return };
+        let Some(job) = jobs.read().get(&shift.job_id).cloned() else { return };
+
+        match shift.clock_out(&job, chrono::Local::now().naive_local(), &clock_out_db) {
+            Ok(_) => error.set(None),
+            Err(e) => error.set(Some(format!("Couldn't clock out: {e}"))),
+        }
+        refresh += 1;
+    };
+
+    let save_db = db.clone();
+    let save = move |_| {
+        let Some(current) = form() else { return };
+        let Some(job) = current.job_id.parse().ok().and_then(|id: i32| jobs.read().get(&id).cloned()) else {
+            error.set(Some("Choose a job for the shift.".to_string()));
+            return
+        };
+        let shift = match current.to_shift(&job, editing()) {
+            Ok(shift) => shift,
+            Err(message) => {
+                error.set(Some(message));
+                return
+            },
+        };
+        let shift = if shift.id == 0 { Shift { id: id_gen.next_shift_id(), ..shift } } else { shift };
+
+        match shift.find_overlap(&save_db) {
+            Ok(Some(other)) => {
+                error.set(Some(format!("This shift overlaps {}.", other.describe())));
+                return
+            },
+            Ok(None) => {},
+            Err(e) => {
+                error.set(Some(format!("Couldn't check for overlapping shifts: {e}")));
+                return
+            },
+        }
+
+        match shift.update(&save_db) {
+            Ok(()) => {
+                form.set(None);
+                editing.set(None);
+                error.set(None);
+            },
+            Err(e) => error.set(Some(format!("Couldn't save the shift: {e}"))),
+        }
+        refresh += 1;
+    };
+
+    rsx!(
+        div { class: "shifts-screen",
+            h2 { "Shifts" }
+            div { class: "clock",
+                match running() {
+                    Some(shift) => rsx!(
+                        p {
+                            {format!(
+                                "Clocked in to {} since {} ({}h {}m)",
+                                jobs.read().get(&shift.job_id).map_or("a deleted job".to_string(), |job| job.name.clone()),
+                                shift.raw_start.unwrap_or(shift.start).format("%H:%M"),
+                                (chrono::Local::now().naive_local() - shift.raw_start.unwrap_or(shift.start)).num_hours(),
+                                (chrono::Local::now().naive_local() - shift.raw_start.unwrap_or(shift.start)).num_minutes() % 60,
+                            )}
+                        }
+                        button { onclick: clock_out, "Clock out" }
+                    ),
+                    None => rsx!(
+                        select {
+                            onchange: move |e| clock_job_id.set(e.value().parse().ok()),
+                            option { value: "", "Choose a job" }
+                            for job in jobs.read().values() {
+                                option { value: "{job.id}", selected: clock_job_id() == Some(job.id), "{job.name}" }
+                            }
+                        }
+                        button { onclick: clock_in, "Clock in" }
+                    ),
+                }
+            }
+
+            button {
+                onclick: move |_| {
+                    let job = clock_job_id().and_then(|id| jobs.read().get(&id).cloned());
+                    let job_overrides = job.as_ref().and_then(|job| overrides.read().get(&job.id).cloned()).unwrap_or_default();
+                    editing.set(None);
+                    form.set(Some(ShiftForm::for_job(job.as_ref(), today, &job_overrides)));
+                    error.set(None);
+                },
+                "Add shift"
+            }
+            if let Some(error) = error() {
+                p { class: "error", "{error}" }
+            }
+            if form().is_some() {
+                ShiftFormView { form }
+                button { onclick: save, if editing().is_some() { "Save changes" } else { "Add shift" } }
+                button { onclick: move |_| { form.set(None); editing.set(None); error.set(None); }, "Cancel" }
+            }
+
+            h3 { "Recent shifts" }
+            table {
+                for shift in recent {
+                    tr { key: "{shift.id}",
+                        td { "{shift.date}" }
+                        td { {jobs.read().get(&shift.job_id).map(|job| job.name.clone()).unwrap_or_default()} }
+                        td { {shift.describe()} }
+                        td { if !shift.is_confirmed { "draft" } }
+                        td {
+                            button {
+                                onclick: {
+                                    let shift = shift.clone();
+                                    move |_| {
+                                        form.set(Some(ShiftForm::from_shift(&shift)));
+                                        editing.set(Some(shift.clone()));
+                                        error.set(None);
+                                    }
+                                },
+                                "Edit"
+                            }
+                            button {
+                                onclick: {
+                                    let db = db.clone();
+                                    let shift = shift.clone();
+                                    move |_| {
+                                        if let Err(e) = shift.delete(&db) {
+                                            error.set(Some(format!("Couldn't delete the shift: {e}")));
+                                        }
+                                        if editing().is_some_and(|edited| edited.id == shift.id) {
+                                            form.set(None);
+                                            editing.set(None);
+                                        }
+                                        refresh += 1;
+                                    }
+                                },
+                                "Delete"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn ShiftFormView(form: Signal<Option<ShiftForm>>) -> Element {
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let overrides = use_context::<Signal<HashMap<i32, Vec<ScheduleOverride>>>>();
+
+    let Some(current) = form() else { return rsx!() };
+    let mut edit = move |change: &dyn Fn(&mut ShiftForm)| {
+        if let Some(form) = form.write().as_mut() { change(form) }
+    };
+    // A new job or date fills the times in again from the rota, the type and break are kept.
+    let mut prefill = move |job_id: String, date: String| {
+        let Some(current) = form() else { return };
+        let job = job_id.parse().ok().and_then(|id: i32| jobs.read().get(&id).cloned());
+        let job_overrides = job.as_ref().and_then(|job| overrides.read().get(&job.id).cloned()).unwrap_or_default();
+
+        match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+            Ok(date) if !current.is_editing => form.set(Some(ShiftForm {
+                shift_type: current.shift_type.clone(),
+                break_minutes: current.break_minutes.clone(),
+                ..ShiftForm::for_job(job.as_ref(), date, &job_overrides)
+            })),
+            _ => form.set(Some(ShiftForm { job_id, date, ..current })),
+        }
+    };
+
+    rsx!(
+        div { class: "shift-form",
+            label { "Job "
+                select {
+                    onchange: move |e| prefill(e.value(), form().map(|f| f.date).unwrap_or_default()),
+                    option { value: "", "Choose a job" }
+                    for job in jobs.read().values() {
+                        option { value: "{job.id}", selected: current.job_id == job.id.to_string(), "{job.name}" }
+                    }
+                }
+            }
+            label { "Date " input { r#type: "date", value: "{current.date}", oninput: move |e| prefill(form().map(|f| f.job_id).unwrap_or_default(), e.value()) } }
+            label { "Start " input { r#type: "time", value: "{current.start}", oninput: move |e| edit(&|f| f.start = e.value()) } }
+            label { "Finish " input { r#type: "time", value: "{current.finish}", oninput: move |e| edit(&|f| f.finish = e.value()) } }
+            label { "Finish date " input { r#type: "date", value: "{current.finish_date}", oninput: move |e| edit(&|f| f.finish_date = e.value()) } }
+            label { "Type "
+                select {
+                    onchange: move |e| edit(&|f| f.shift_type = e.value()),
+                    for shift_type in ShiftType::all() {
+                        option { value: "{shift_type:?}", selected: current.shift_type == format!("{shift_type:?}"), "{shift_type:?}" }
+                    }
+                }
+            }
+            label { "Unpaid break (minutes) " input { r#type: "number", value: "{current.break_minutes}", placeholder: "Job's break rules", oninput: move |e| edit(&|f| f.break_minutes = e.value()) } }
+            if current.has_segments {
+                p { "This is a split shift, the start and finish change its first and last part." }
+            }
+        }
+    )
+}
+
+// Inputs for a shift as typed, checked and converted by to_shift.
+#[derive(PartialEq, Debug, Clone, Default)]
+struct ShiftForm {
+    job_id: String,
+    date: String,
+    start: String,
+    finish: String,
+    finish_date: String, // Differs from date for shifts that run past midnight
+    shift_type: String,
+    break_minutes: String, // Empty = the job's break rules
+    is_editing: bool,
+    has_segments: bool,
+}
+impl ShiftForm {
+    // Times come from the rota when the job has them for the date.
+    fn for_job(job: Option<&Job>, date: NaiveDate, overrides: &[ScheduleOverride]) -> ShiftForm {
+        let scheduled = job.and_then(|job| job.get_scheduled_shift_on(date, overrides));
+        let shift_type = match &scheduled {
+            Some(day) if day.status == ShiftStatus::OFF => ShiftType::ExtraShift,
+            _ => ShiftType::Scheduled,
+        };
+        let time = |value: Option<NaiveDateTime>| value.map(|time| time.format("%H:%M").to_string()).unwrap_or_default();
+
+        ShiftForm {
+            job_id: job.map(|job| job.id.to_string()).unwrap_or_default(),
+            date: date.to_string(),
+            start: time(scheduled.as_ref().and_then(|day| day.start)),
+            finish: time(scheduled.as_ref().and_then(|day| day.finish)),
+            finish_date: scheduled.as_ref().and_then(|day| day.finish).map_or(date, |finish| finish.date()).to_string(),
+            shift_type: format!("{shift_type:?}"),
+            ..Default::default()
+        }
+    }
+
+    // Shows the times as clocked, rounding is applied again on save.
+    fn from_shift(shift: &Shift) -> ShiftForm {
+        let start = shift.raw_start.unwrap_or(shift.start);
+        let finish = shift.raw_finish.unwrap_or(shift.finish);
+
+        ShiftForm {
+            job_id: shift.job_id.to_string(),
+            date: shift.date.to_string(),
+            start: start.format("%H:%M").to_string(),
+            finish: finish.format("%H:%M").to_string(),
+            finish_date: finish.date().to_string(),
+            shift_type: format!("{:?}", shift.shift_type),
+            break_minutes: shift.unpaid_break.map(|taken| taken.duration.num_minutes().to_string()).unwrap_or_default(),
+            is_editing: true,
+            has_segments: !shift.segments.is_empty(),
+        }
+    }
+
+    // A new shift has id 0, an edited one keeps everything the form doesn't show.
+    fn to_shift(&self, job: &Job, editing: Option<Shift>) -> Result<Shift, String> {
+        let date = JobForm::parse_date(&self.date, "Date")?.ok_or("Choose the date of the shift.")?;
+        let start = JobForm::parse_time(&self.start, "Start")?.ok_or("Enter the start time.")?;
+        let finish = JobForm::parse_time(&self.finish, "Finish")?.ok_or("Enter the finish time.")?;
+        let finish_date = JobForm::parse_date(&self.finish_date, "Finish date")?.unwrap_or(date);
+
+        let start = date.and_time(start);
+        let finish = finish_date.and_time(finish);
+        if finish <= start {
+            return Err("The finish must be after the start, change the finish date for shifts past midnight.".to_string())
+        }
+        if finish - start > TimeDelta::hours(24) {
+            return Err("A shift can't be longer than 24 hours.".to_string())
+        }
+
+        let shift_type = ShiftType::all()
+            .into_iter()
+            .find(|shift_type| format!("{shift_type:?}") == self.shift_type)
+            .unwrap_or(ShiftType::Scheduled);
+        let unpaid_break = match Self::parse_break(&self.break_minutes)? {
+            Some(duration) if duration >= finish - start => return Err("The break is longer than the shift.".to_string()),
+            duration => duration.map(|duration| ShiftBreak { start: None, duration }),
+        };
+
+        let shift = match editing {
+            Some(original) => Shift {
+                segments: Self::get_segments_within(&original, date, start, finish),
+                job_id: job.id,
+                date,
+                date_key: Shift::date_to_key(date),
+                shift_type,
+                start,
+                finish,
+                unpaid_break,
+                raw_start: None,
+                raw_finish: None,
+                is_confirmed: true,
+                is_lieu_day: original.is_lieu_day && shift_type == ShiftType::PaidLeave,
+                ..original
+            },
+            None => Shift { unpaid_break, ..Shift::new(0, job.id, date, shift_type, start, finish) },
+        };
+
+        Ok(shift.with_rounding(job))
+    }
+
+    // The form only edits the outer times, a split shift keeps its segments clipped to them.
+    // Moving the shift to another date drops them.
+    fn get_segments_within(original: &Shift, date: NaiveDate, start: NaiveDateTime, finish: NaiveDateTime) -> Vec<ShiftSegment> {
+        if original.date != date { return Vec::new() }
+
+        let mut segments: Vec<ShiftSegment> = original.segments
+            .iter()
+            .filter(|segment| segment.finish > start && segment.start < finish)
+            .map(|segment| ShiftSegment { start: segment.start.max(start), finish: segment.finish.min(finish) })
+            .collect();
+        if let Some(first) = segments.first_mut() { first.start = start }
+        if let Some(last) = segments.last_mut() { last.finish = finish }
+
+        Shift::new(original.id, original.job_id, date, original.shift_type, start, finish)
+            .with_segments(segments)
+            .segments
+    }
+
+    // Zero is a recorded break of no time, which is different from leaving it empty.
+    fn parse_break(value: &str) -> Result<Option<Duration>, String> {
+        match JobForm::parse_number::<i64>(value, "Unpaid break")? {
+            Some(minutes) if minutes < 0 => Err("Unpaid break can't be negative.".to_string()),
+            minutes => Ok(minutes.map(Duration::minutes)),
+        }
+    }
+}
+
+// Builds a job's salary multipliers and shows how the last month would be repriced.
+#[component]
+fn MultipliersScreen() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let id_gen = use_context::<Arc<IdGenerator>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let mut multipliers = use_context::<Signal<HashMap<i32, Vec<SalaryMultiplier>>>>();
+
+    let mut job_id = use_signal(|| None::<i32>);
+    let mut form = use_signal(|| None::<MultiplierForm>);
+    let mut editing_id = use_signal(|| None::<i32>); // None = a new multiplier
+    let mut error = use_signal(|| None::<String>);
+
+    let save_db = db.clone();
+    let save = move |_| {
+        let (Some(job_id), Some(current)) = (job_id(), form()) else { return };
+        let multiplier = match current.to_multiplier(job_id) {
+            Ok(multiplier) => multiplier,
+            Err(message) => {
+                error.set(Some(message));
+                return
+            },
+        };
+        let multiplier = SalaryMultiplier { id: editing_id().unwrap_or_else(|| id_gen.next_salary_multiplier_id()), ..multiplier };
+
+        match multiplier.update(&save_db) {
+            Ok(()) => {
+                let mut all = multipliers.write();
+                let job_multipliers = all.entry(job_id).or_default();
+                job_multipliers.retain(|saved| saved.id != multiplier.id);
+                job_multipliers.push(multiplier);
+                form.set(None);
+                error.set(None);
+            },
+            Err(e) => error.set(Some(format!("Couldn't save the multiplier: {e}"))),
+        }
+    };
+
+    let job = job_id().and_then(|id| jobs.read().get(&id).cloned());
+    let saved = job.as_ref().and_then(|job| SalaryMultiplier::get_for(job, &multipliers.read())).unwrap_or_default();
+
+    rsx!(
+        div { class: "multipliers-screen",
+            h2 { "Multipliers" }
+            select {
+                onchange: move |e| {
+                    job_id.set(e.value().parse().ok());
+                    form.set(None);
+                    error.set(None);
+                },
+                option { value: "", "Choose a job" }
+                for job in jobs.read().values() {
+                    option { value: "{job.id}", selected: job_id() == Some(job.id), "{job.name}" }
+                }
+            }
+            if let Some(job) = job {
+                table {
+                    for multiplier in saved.iter().cloned() {
+                        tr { key: "{multiplier.id}",
+                            td { "{multiplier.name}" }
+                            td { "x{multiplier.multiplier.to_floating_point()}" }
+                            td { "{multiplier.priority:?}, {multiplier.behavior:?}" }
+                            td {
+                                button {
+                                    onclick: {
+                                        let multiplier = multiplier.clone();
+                                        move |_| {
+                                            editing_id.set(Some(multiplier.id));
+                                            form.set(Some(MultiplierForm::from_multiplier(&multiplier)));
+                                            error.set(None);
+                                        }
+                                    },
+                                    "Edit"
+                                }
+                                button {
+                                    onclick: {
+                                        let db = db.clone();
+                                        move |_| match multiplier.delete(&db) {
+                                            Ok(()) => {
+                                                if let Some(job_multipliers) = multipliers.write().get_mut(&multiplier.job_id) {
+                                                    job_multipliers.retain(|saved| saved.id != multiplier.id);
+                                                }
+                                                if editing_id() == Some(multiplier.id) { form.set(None) }
+                                            },
+                                            Err(e) => error.set(Some(format!("Couldn't delete the multiplier: {e}"))),
+                                        }
+                                    },
+                                    "Delete"
+                                }
+                            }
+                        }
+                    }
+                }
+                button {
+                    onclick: move |_| {
+                        editing_id.set(None);
+                        form.set(Some(MultiplierForm::new(chrono::Local::now().date_naive())));
+                        error.set(None);
+                    },
+                    "New multiplier"
+                }
+                if let Some(error) = error() {
+                    p { class: "error", "{error}" }
+                }
+                if let Some(current) = form() {
+                    MultiplierFormView { form }
+                    button { onclick: save, if editing_id().is_some() { "Save changes" } else { "Create multiplier" } }
+                    button { onclick: move |_| { form.set(None); error.set(None); }, "Cancel" }
+                    match current.to_multiplier(job.id) {
+                        Ok(edited) => rsx!( MultiplierPreview { job: job.clone(), edited: SalaryMultiplier { id: editing_id().unwrap_or(0), ..edited } } ),
+                        Err(message) => rsx!( p { "Preview not available: {message}" } ),
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn MultiplierFormView(form: Signal<Option<MultiplierForm>>) -> Element {
+    let Some(current) = form() else { return rsx!() };
+    let mut edit = move |change: &dyn Fn(&mut MultiplierForm)| {
+        if let Some(form) = form.write().as_mut() { change(form) }
+    };
+    let weekdays = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let crosses_midnight = matches!(
+        (JobForm::parse_time(&current.window_start, ""), JobForm::parse_time(&current.window_end, "")),
+        (Ok(Some(start)), Ok(Some(end))) if end < start
+    );
+
+    rsx!(
+        div { class: "multiplier-form",
+            label { "Name " input { value: "{current.name}", oninput: move |e| edit(&|f| f.name = e.value()) } }
+            label { "Description " input { value: "{current.description}", oninput: move |e| edit(&|f| f.description = e.value()) } }
+            label { "Multiplier " input { value: "{current.value}", placeholder: "1.5", oninput: move |e| edit(&|f| f.value = e.value()) } }
+            label { "Priority "
+                select {
+                    onchange: move |e| edit(&|f| f.priority = e.value()),
+                    for (value, name) in [("always", "Always apply"), ("low", "Low"), ("medium", "Medium"), ("high", "High")] {
+                        option { value: "{value}", selected: current.priority == value, "{name}" }
+                    }
+                }
+            }
+            label { "Combines "
+                select {
+                    onchange: move |e| edit(&|f| f.behavior = e.value()),
+                    option { value: "compound", selected: current.behavior == "compound", "With the others" }
+                    option { value: "highest", selected: current.behavior == "highest", "Highest only" }
+                }
+            }
+
+            h4 { "When" }
+            select {
+                onchange: move |e| edit(&|f| f.schedule = e.value()),
+                for (value, name) in [("weekly", "Every week"), ("monthly", "Every month"), ("dates", "On dates"), ("once", "Once"), ("period", "Yearly period")] {
+                    option { value: "{value}", selected: current.schedule == value, "{name}" }
+                }
+            }
+            match current.schedule.as_str() {
+                "weekly" => rsx!(
+                    for (day, weekday) in weekdays.iter().enumerate() {
+                        label { key: "{day}",
+                            input { r#type: "checkbox", checked: current.weekdays[day], onchange: move |e| edit(&|f| f.weekdays[day] = e.checked()) }
+                            "{weekday}"
+                        }
+                    }
+                ),
+                "monthly" => rsx!(
+                    label { "Days of the month " input { value: "{current.days_of_month}", placeholder: "1, 15", oninput: move |e| edit(&|f| f.days_of_month = e.value()) } }
+                ),
+                "dates" | "once" => rsx!(
+                    label { "Dates " input { value: "{current.dates}", placeholder: "2026-12-25, 2026-12-26", oninput: move |e| edit(&|f| f.dates = e.value()) } }
+                ),
+                _ => rsx!(
+                    label { "From " input { value: "{current.period_start}", placeholder: "24/12", oninput: move |e| edit(&|f| f.period_start = e.value()) } }
+                    input { r#type: "time", value: "{current.period_start_time}", oninput: move |e| edit(&|f| f.period_start_time = e.value()) }
+                    label { " to " input { value: "{current.period_end}", placeholder: "27/12", oninput: move |e| edit(&|f| f.period_end = e.value()) } }
+                    input { r#type: "time", value: "{current.period_end_time}", oninput: move |e| edit(&|f| f.period_end_time = e.value()) }
+                ),
+            }
+            if current.schedule == "weekly" || current.schedule == "monthly" {
+                label { "Starting " input { r#type: "date", value: "{current.start_date}", oninput: move |e| edit(&|f| f.start_date = e.value()) } }
+                label { "Until " input { r#type: "date", value: "{current.end_date}", oninput: move |e| edit(&|f| f.end_date = e.value()) } }
+            }
+            if current.schedule != "period" {
+                label { "Only between "
+                    input { r#type: "time", value: "{current.window_start}", oninput: move |e| edit(&|f| f.window_start = e.value()) }
+                    " and "
+                    input { r#type: "time", value: "{current.window_end}", oninput: move |e| edit(&|f| f.window_end = e.value()) }
+                }
+                if crosses_midnight {
+                    p { "Runs past midnight into the next morning." }
+                } else if current.window_start.is_empty() {
+                    p { "Leave the times empty to apply to the whole shift." }
+                }
+            }
+        }
+    )
+}
+
+// Last month's shifts priced with the saved multipliers and again with the edited one.
+#[component]
+fn MultiplierPreview(job: Job, edited: SalaryMultiplier) -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let multipliers = use_context::<Signal<HashMap<i32, Vec<SalaryMultiplier>>>>();
+    let bank_holidays = use_context::<Signal<Vec<BankHoliday>>>();
+
+    let today = chrono::Local::now().date_naive();
+    let from = today.checked_sub_months(chrono::Months::new(1)).unwrap_or(today);
+    let saved = SalaryMultiplier::get_for(&job, &multipliers.read()).unwrap_or_default();
+    let mut repriced: Vec<SalaryMultiplier> = saved.iter().filter(|m| m.id != edited.id).cloned().collect();
+    repriced.push(edited);
+
+    let holidays = BankHolidayChecker::for_job_between(&job, from, today, &bank_holidays.read());
+    let rows = RepricedShift::for_period(&db, &job, saved, repriced, &holidays, from, today).unwrap_or_default();
+    let total_before: u32 = rows.iter().map(|row| row.before).sum();
+    let total_after: u32 = rows.iter().map(|row| row.after).sum();
+    let change = |before: u32, after: u32| match after.cmp(&before) {
+        std::cmp::Ordering::Greater => format!("+{}", format_pence(after - before)),
+        std::cmp::Ordering::Less => format!("-{}", format_pence(before - after)),
+        std::cmp::Ordering::Equal => String::new(),
+    };
+
+    rsx!(
+        div { class: "multiplier-preview",
+            h4 { {format!("Shifts since {}", from.format("%d/%m/%Y"))} }
+            if rows.is_empty() {
+                p { "No shifts in the last month to preview." }
+            }
+            table {
+                tr { th { "" } th { "Now" } th { "Edited" } th { "Change" } }
+                for RepricedShift { description, lines, before, after } in rows {
+                    tr { td { strong { "{description}" } } td { {format_pence(before)} } td { {format_pence(after)} } td { {change(before, after)} } }
+                    for (name, line_before, line_after) in lines {
+                        tr { td { "  {name}" } td { {format_pence(line_before)} } td { {format_pence(line_after)} } td { {change(line_before, line_after)} } }
+                    }
+                }
+                tr { td { strong { "Total" } } td { {format_pence(total_before)} } td { {format_pence(total_after)} } td { {change(total_before, total_after)} } }
+            }
+        }
+    )
+}
+
+// One shift priced with the saved multipliers and again with the edited ones.
+#[derive(PartialEq, Debug, Clone)]
+struct RepricedShift {
+    description: String,
+    lines: Vec<(String, u32, u32)>, // Pay line name, before and after, matched up by type
+    before: u32,
+    after: u32,
+}
+impl RepricedShift {
+    // Confirmed shifts that have finished, in start order.
+    fn for_period(
+        db: &Database,
+        job: &Job,
+        saved: Vec<SalaryMultiplier>,
+        repriced: Vec<SalaryMultiplier>,
+        holidays: &BankHolidayChecker,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<RepricedShift>, Error> {
+        let mut shifts = job.get_shifts_for_period_of(from, to, db)?;
+        shifts.retain(|shift| shift.is_confirmed && !shift.is_running);
+        shifts.sort_by_key(|shift| shift.start);
+
+        let mut rows = Vec::new();
+        for shift in &shifts {
+            let before = ShiftPayment::new_for_shift(shift, job, db, saved.clone(), holidays)?;
+            let after = ShiftPayment::new_for_shift(shift, job, db, repriced.clone(), holidays)?;
+
+            let mut lines: Vec<(String, u32, u32)> = Vec::new();
+            for (payments, is_after) in [(&before, false), (&after, true)] {
+                for payment in payments {
+                    let name = format!("{:?}", payment.payment_type);
+                    let index = lines.iter().position(|line| line.0 == name).unwrap_or_else(|| {
+                        lines.push((name, 0, 0));
+                        lines.len() - 1
+                    });
+                    if is_after { lines[index].2 += payment.amount } else { lines[index].1 += payment.amount }
+                }
+            }
+            rows.push(RepricedShift {
+                description: shift.describe(),
+                lines,
+                before: ShiftPayment::get_total(&before),
+                after: ShiftPayment::get_total(&after),
+            });
+        }
+
+        Ok(rows)
+    }
+}
+
+// Inputs for a SalaryMultiplier as typed, checked and converted by to_multiplier.
+#[derive(PartialEq, Debug, Clone, Default)]
+struct MultiplierForm {
+    name: String,
+    description: String,
+    value: String, // e.g. 1.5
+    priority: String,
+    behavior: String,
+    schedule: String, // "weekly", "monthly", "dates", "once" or "period"
+    weekdays: [bool; 7],
+    days_of_month: String, // e.g. "1, 15"
+    dates: String, // e.g. "2026-12-25, 2026-12-26"
+    start_date: String,
+    end_date: String,
+    window_start: String, // Both empty = the whole shift
+    window_end: String,
+    period_start: String, // Day and month, e.g. "24/12"
+    period_start_time: String,
+    period_end: String,
+    period_end_time: String,
+    one_time_shift: Option<(NaiveDate, i32)>, // A one-off tied to a shift keeps it while the date is unchanged
+}
+impl MultiplierForm {
+    fn new(today: NaiveDate) -> MultiplierForm {
+        MultiplierForm {
+            value: "1.5".to_string(),
+            priority: "medium".to_string(),
+            behavior: "compound".to_string(),
+            schedule: "weekly".to_string(),
+            start_date: today.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn from_multiplier(multiplier: &SalaryMultiplier) -> MultiplierForm {
+        let time = |time: NaiveTime| time.format("%H:%M").to_string();
+        let join_dates = |dates: &[NaiveDate]| dates.iter().map(NaiveDate::to_string).collect::<Vec<_>>().join(", ");
+
+        let mut form = MultiplierForm {
+            name: multiplier.name.clone(),
+            description: multiplier.description.clone().unwrap_or_default(),
+            value: multiplier.multiplier.to_floating_point().to_string(),
+            priority: match multiplier.priority {
+                MultiplierPriority::AlwaysApply => "always",
+                MultiplierPriority::Low => "low",
+                MultiplierPriority::Medium => "medium",
+                MultiplierPriority::High => "high",
+            }.to_string(),
+            behavior: if multiplier.behavior == MultiplierBehavior::HighestOnly { "highest" } else { "compound" }.to_string(),
+            window_start: multiplier.time_window.map(|window| time(window.start)).unwrap_or_default(),
+            window_end: multiplier.time_window.map(|window| time(window.end)).unwrap_or_default(),
+            ..Default::default()
+        };
+
+        match &multiplier.schedule {
+            ReocurrementSchedule::Weekly { weekdays, start_date, end_date } => {
+                form.schedule = "weekly".to_string();
+                for weekday in weekdays {
+                    form.weekdays[weekday.num_days_from_monday() as usize] = true;
+                }
+                form.start_date = start_date.to_string();
+                form.end_date = end_date.map(|date| date.to_string()).unwrap_or_default();
+            },
+            ReocurrementSchedule::Monthly { day_of_month, start_date, end_date } => {
+                form.schedule = "monthly".to_string();
+                form.days_of_month = day_of_month.iter().map(u8::to_string).collect::<Vec<_>>().join(", ");
+                form.start_date = start_date.to_string();
+                form.end_date = end_date.map(|date| date.to_string()).unwrap_or_default();
+            },
+            ReocurrementSchedule::SpecificDates { dates } => {
+                form.schedule = "dates".to_string();
+                form.dates = join_dates(dates);
+            },
+            ReocurrementSchedule::OneTime { date, shift_id, .. } => {
+                form.schedule = "once".to_string();
+                form.dates = date.to_string();
+                form.one_time_shift = shift_id.map(|id| (*date, id));
+            },
+        }
+
+        if let Some(period) = multiplier.premium_period {
+            form.schedule = "period".to_string();
+            form.period_start = format!("{}/{}", period.start_day, period.start_month);
+            form.period_start_time = time(period.start_time);
+            form.period_end = format!("{}/{}", period.end_day, period.end_month);
+            form.period_end_time = time(period.end_time);
+        }
+
+        form
+    }
+
+    // The multiplier with id 0, or a message saying which field is wrong.
+    fn to_multiplier(&self, job_id: i32) -> Result<SalaryMultiplier, String> {
+        let name = self.name.trim();
+        if name.is_empty() { return Err("Enter a name for the multiplier.".to_string()) }
+
+        let value = JobForm::parse_number::<f32>(&self.value, "Multiplier")?
+            .filter(|value| *value > 0.0 && *value <= 10.0)
+            .ok_or("Enter a multiplier between 0 and 10, e.g. 1.5")?;
+
+        let (schedule, premium_period) = self.get_schedule()?;
+
+        let window_start = JobForm::parse_time(&self.window_start, "Window start")?;
+        let window_end = JobForm::parse_time(&self.window_end, "Window end")?;
+        let time_window = match (window_start, window_end) {
+            _ if premium_period.is_some() => None,
+            (Some(start), Some(end)) if start == end => return Err("The time window starts and ends at the same time.".to_string()),
+            (Some(start), Some(end)) => Some(TimeWindow { start, end }),
+            (None, None) => None,
+            _ => return Err("Give the time window both a start and an end, or neither.".to_string()),
+        };
+
+        Ok(SalaryMultiplier {
+            id: 0,
+            job_id,
+            behavior: if self.behavior == "highest" { MultiplierBehavior::HighestOnly } else { MultiplierBehavior::Compound },
+            priority: match self.priority.as_str() {
+                "always" => MultiplierPriority::AlwaysApply,
+                "low" => MultiplierPriority::Low,
+                "high" => MultiplierPriority::High,
+                _ => MultiplierPriority::Medium,
+            },
+            name: name.to_string(),
+            description: Some(self.description.trim().to_string()).filter(|description| !description.is_empty()),
+            schedule,
+            multiplier: Multiplier::from_floating_point(value),
+            time_window,
+            premium_period,
+        })
+    }
+
+    // A premium period still needs a schedule, it isn't consulted while the period is set.
+    fn get_schedule(&self) -> Result<(ReocurrementSchedule, Option<PremiumPeriod>), String> {
+        let start_date = JobForm::parse_date(&self.start_date, "Starting")?;
+        let end_date = JobForm::parse_date(&self.end_date, "Until")?;
+        let dates = || -> Result<Vec<NaiveDate>, String> {
+            let mut dates = Vec::new();
+            for date in self.dates.split(',').map(str::trim).filter(|date| !date.is_empty()) {
+                dates.push(JobForm::parse_date(date, date)?.unwrap());
+            }
+            if dates.is_empty() { return Err("Enter at least one date, e.g. 2026-12-25".to_string()) }
+            Ok(dates)
+        };
+
+        let schedule = match self.schedule.as_str() {
+            "weekly" => {
+                let weekdays: Vec<Weekday> = (0..7)
+                    .filter(|day| self.weekdays[*day as usize])
+                    .map(|day| Weekday::try_from(day).unwrap())
+                    .collect();
+                if weekdays.is_empty() { return Err("Tick at least one day of the week.".to_string()) }
+                ReocurrementSchedule::Weekly { weekdays, start_date: start_date.ok_or("Choose when the multiplier starts.")?, end_date }
+            },
+            "monthly" => {
+                let mut day_of_month = Vec::new();
+                for day in self.days_of_month.split(',').map(str::trim).filter(|day| !day.is_empty()) {
+                    match day.parse::<u8>() {
+                        Ok(day) if (1..=31).contains(&day) => day_of_month.push(day),
+                        _ => return Err(format!("\"{day}\" isn't a day of the month.")),
+                    }
+                }
+                if day_of_month.is_empty() { return Err("Enter the days of the month, e.g. 1, 15".to_string()) }
+                ReocurrementSchedule::Monthly { day_of_month, start_date: start_date.ok_or("Choose when the multiplier starts.")?, end_date }
+            },
+            "once" => {
+                let [date] = dates()?[..] else { return Err("A one-off multiplier is for a single date.".to_string()) };
+                let shift_id = self.one_time_shift.filter(|(shift_date, _)| *shift_date == date).map(|(_, id)| id);
+                ReocurrementSchedule::OneTime { date, date_key: Shift::date_to_key(date), shift_id }
+            },
+            "dates" => ReocurrementSchedule::SpecificDates { dates: dates()? },
+            _ => {
+                let (start_day, start_month) = Self::parse_day_month(&self.period_start, "From")?;
+                let (end_day, end_month) = Self::parse_day_month(&self.period_end, "To")?;
+                let period = PremiumPeriod {
+                    start_month,
+                    start_day,
+                    start_time: JobForm::parse_time(&self.period_start_time, "From time")?.unwrap_or(NaiveTime::MIN),
+                    end_month,
+                    end_day,
+                    end_time: JobForm::parse_time(&self.period_end_time, "To time")?.unwrap_or(NaiveTime::MIN),
+                };
+                return Ok((ReocurrementSchedule::SpecificDates { dates: Vec::new() }, Some(period)))
+            },
+        };
+
+        if let (Some(start), Some(end)) = (start_date, end_date) && end < start {
+            return Err("The multiplier ends before it starts.".to_string())
+        }
+        Ok((schedule, None))
+    }
+
+    // e.g. "24/12", checked against a leap year so 29/02 is allowed.
+    fn parse_day_month(value: &str, field: &str) -> Result<(u32, u32), String> {
+        let error = || format!("{field} needs a day and month, e.g. 24/12");
+        let (day, month) = value.trim().split_once('/').ok_or_else(error)?;
+        let (day, month) = (day.trim().parse().map_err(|_| error())?, month.trim().parse().map_err(|_| error())?);
+        NaiveDate::from_ymd_opt(2024, month, day).ok_or_else(error)?;
+        Ok((day, month))
+    }
+}
+
+// Payslip for one job and pay period, laid out like a UK payslip.
+#[component]
+fn PayslipScreen() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let multipliers = use_context::<Signal<HashMap<i32, Vec<SalaryMultiplier>>>>();
+    let bank_holidays = use_context::<Signal<Vec<BankHoliday>>>();
+
+    let mut job_id = use_signal(|| None::<i32>);
+    let mut period = use_signal(|| PayslipPeriod::Monthly);
+    let mut date = use_signal(|| chrono::Local::now().date_naive());
+
+    let job = job_id().and_then(|id| jobs.read().get(&id).cloned());
+    // Year to date adds up every earlier period, so it's only worked out again when a choice changes.
+    let payslip = use_memo(move || {
+        let job = job_id().and_then(|id| jobs.read().get(&id).cloned())?;
+        Some(Payslip::new(&db, &job, &multipliers.read(), &bank_holidays.read(), period(), date()).map_err(|e| e.to_string()))
+    });
+    let pence = |amount: u32| format_pence(amount);
+
+    rsx!(
+        div { class: "payslip-screen",
+            h2 { "Payslip" }
+            select {
+                onchange: move |e| job_id.set(e.value().parse().ok()),
+                option { value: "", "Choose a job" }
+                for job in jobs.read().values() {
+                    option { value: "{job.id}", selected: job_id() == Some(job.id), "{job.name}" }
+                }
+            }
+            select {
+                onchange: move |e| period.set(if e.value() == "weekly" { PayslipPeriod::Weekly } else { PayslipPeriod::Monthly }),
+                option { value: "monthly", selected: period() == PayslipPeriod::Monthly, "Monthly" }
+                option { value: "weekly", selected: period() == PayslipPeriod::Weekly, "Weekly" }
+            }
+            match (job, payslip()) {
+                (Some(job), Some(Ok(payslip))) => {
+                    let previous = payslip.period_start.pred_opt().unwrap();
+                    let next = payslip.period_end.succ_opt().unwrap();
+                    let totals = payslip.this_period;
+                    let ytd = payslip.year_to_date;
+                    let deductions: Vec<(String, u32)> = [
+                        ("Income tax".to_string(), totals.tax),
+                        ("National Insurance".to_string(), totals.national_insurance),
+                        ("Student loan".to_string(), totals.student_loan),
+                        ("Pension".to_string(), totals.pension),
+                    ]
+                    .into_iter()
+                    .filter(|(_, amount)| *amount > 0)
+                    .chain(payslip.pre_tax_deductions.iter().cloned())
+                    .chain(payslip.post_tax_deductions.iter().cloned())
+                    .collect();
+                    let total_deductions: u32 = deductions.iter().map(|(_, amount)| amount).sum();
+
+                    rsx!(
+                        div { class: "payslip-navigation",
+                            button { onclick: move |_| date.set(previous), "Previous" }
+                            button { onclick: move |_| date.set(next), "Next" }
+                        }
+                        div { class: "payslip", style: "border: 1px solid #333; padding: 8px; max-width: 720px;",
+                            div { class: "payslip-header",
+                                strong { "{job.name}" }
+                                p {
+                                    match payslip.period {
+                                        PayslipPeriod::Weekly => format!("Tax week {}", payslip.period_number),
+                                        PayslipPeriod::Monthly => format!("Tax month {}", payslip.period_number),
+                                    }
+                                    ", {payslip.financial_year}"
+                                }
+                                p { {format!("Pay period {} to {}", payslip.period_start.format("%d/%m/%Y"), payslip.period_end.format("%d/%m/%Y"))} }
+                            }
+                            div { style: "display: flex; gap: 16px;",
+                                table { class: "payments",
+                                    tr { th { "Payments" } th { "Hours" } th { "Rate" } th { "Amount" } }
+                                    for line in payslip.lines.iter().chain(payslip.extras.iter()) {
+                                        tr {
+                                            td { "{line.name}" }
+                                            td { {line.get_hours().map(|hours| format!("{hours:.2}")).unwrap_or_default()} }
+                                            td { {line.get_rate().map(pence).unwrap_or_default()} }
+                                            td { {pence(line.amount)} }
+                                        }
+                                    }
+                                    tr { td { strong { "Total gross pay" } } td {} td {} td { strong { {pence(totals.gross)} } } }
+                                    for line in payslip.post_tax_additions.iter() {
+                                        tr { td { "{line.name} (after tax)" } td {} td {} td { {pence(line.amount)} } }
+                                    }
+                                }
+                                table { class: "deductions",
+                                    tr { th { "Deductions" } th { "Amount" } }
+                                    for (name, amount) in deductions {
+                                        tr { td { "{name}" } td { {pence(amount)} } }
+                                    }
+                                    tr { td { strong { "Total deductions" } } td { strong { {pence(total_deductions)} } } }
+                                }
+                            }
+                            div { style: "display: flex; gap: 16px;",
+                                table { class: "year-to-date",
+                                    tr { th { "Year to date" } th { "" } }
+                                    tr { td { "Gross pay" } td { {pence(ytd.gross)} } }
+                                    tr { td { "Taxable pay" } td { {pence(ytd.taxable)} } }
+                                    tr { td { "Income tax" } td { {pence(ytd.tax)} } }
+                                    tr { td { "Earnings for NI" } td { {pence(ytd.niable)} } }
+                                    tr { td { "National Insurance" } td { {pence(ytd.national_insurance)} } }
+                                    if ytd.student_loan > 0 {
+                                        tr { td { "Student loan" } td { {pence(ytd.student_loan)} } }
+                                    }
+                                    if ytd.pension > 0 {
+                                        tr { td { "Pension" } td { {pence(ytd.pension)} } }
+                                    }
+                                    tr { td { "Net pay" } td { {pence(ytd.net)} } }
+                                }
+                                table { class: "this-period",
+                                    tr { th { "This period" } th { "" } }
+                                    tr { td { "Taxable pay" } td { {pence(totals.taxable)} } }
+                                    tr { td { "Earnings for NI" } td { {pence(totals.niable)} } }
+                                    tr { td { strong { "Net pay" } } td { strong { {pence(totals.net)} } } }
+                                }
+                            }
+                            p { "Tax worked out on a week 1/month 1 basis." }
+                        }
+                    )
+                },
+                (Some(_), Some(Err(e))) => rsx!( p { class: "error", "Couldn't work out the payslip: {e}" } ),
+                _ => rsx!(),
+            }
+        }
+    )
+}
+
+// Earnings over a tax year, charted per week or month.
+#[component]
+fn Dashboard() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let multipliers = use_context::<Signal<HashMap<i32, Vec<SalaryMultiplier>>>>();
+    let bank_holidays = use_context::<Signal<Vec<BankHoliday>>>();
+
+    let today = chrono::Local::now().date_naive();
+    let current_tax_year = TaxWeek::get_year_cycle_of_financial_year(today);
+    let mut job_id = use_signal(|| None::<i32>); // None = all jobs
+    let mut tax_year = use_signal(|| current_tax_year);
+    let mut period = use_signal(|| PayslipPeriod::Monthly);
+
+    // Every period is priced from the database, so it's only worked out again when a filter changes.
+    let dashboard = use_memo(move || {
+        let all_jobs = jobs.read();
+        let mut shown: Vec<&Job> = all_jobs.values().filter(|job| job_id().is_none_or(|id| id == job.id)).collect();
+        shown.sort_by(|a, b| a.name.cmp(&b.name));
+
+        EarningsDashboard::new(&db, &shown, &multipliers.read(), &bank_holidays.read(), period(), tax_year(), today)
+            .map_err(|e| e.to_string())
+    });
+    let all_jobs = jobs.read();
+
+    rsx!(
+        div { class: "dashboard",
+            h2 { "Dashboard" }
+            div { class: "dashboard-filters",
+                select {
+                    onchange: move |e| job_id.set(e.value().parse().ok()),
+                    option { value: "", "All jobs" }
+                    for job in all_jobs.values() {
+                        option { value: "{job.id}", selected: job_id() == Some(job.id), "{job.name}" }
+                    }
+                }
+                select {
+                    onchange: move |e| if let Ok(year) = e.value().parse() { tax_year.set(year) },
+                    for year in (current_tax_year - 4..=current_tax_year).rev() {
+                        option { value: "{year}", selected: tax_year() == year, "{year}/{year + 1}" }
+                    }
+                }
+                select {
+                    onchange: move |e| period.set(if e.value() == "weekly" { PayslipPeriod::Weekly } else { PayslipPeriod::Monthly }),
+                    option { value: "monthly", selected: period() == PayslipPeriod::Monthly, "By month" }
+                    option { value: "weekly", selected: period() == PayslipPeriod::Weekly, "By week" }
+                }
+            }
+            match dashboard() {
+                Ok(dashboard) => {
+                    let labels: Vec<String> = dashboard.periods.iter().map(|earnings| earnings.label.clone()).collect();
+                    let money = |values: Vec<u32>| ChartSeries::new_money(values);
+                    let totals = dashboard.get_totals();
+                    let last_year = dashboard.get_last_year_totals();
+                    let change = |this: u32, last: u32| match last {
+                        0 => String::new(),
+                        last => format!(" ({:+.1}%)", (this as f32 - last as f32) / last as f32 * 100.0),
+                    };
+                    let premium = dashboard.get_premium_seconds();
+
+                    rsx!(
+                        div { class: "dashboard-stats", style: "display: flex; gap: 24px;",
+                            div { "Gross to date: {format_pence(totals.gross)}" {change(totals.gross, last_year.gross)} }
+                            div { "Net to date: {format_pence(totals.net)}" {change(totals.net, last_year.net)} }
+                            div { {format!("Hours: {:.1}", dashboard.get_seconds() as f32 / 3600.0)} }
+                            div {
+                                "Average hourly rate: "
+                                {dashboard.get_average_hourly_rate().map(format_pence).unwrap_or("-".to_string())}
+                            }
+                        }
+                        p {
+                            {format!(
+                                "Same point last year: {} gross, {} net",
+                                format_pence(last_year.gross),
+                                format_pence(last_year.net),
+                            )}
+                        }
+                        BarChart {
+                            title: "Gross and net pay",
+                            labels: labels.clone(),
+                            series: vec![
+                                ChartSeries { name: "Gross".to_string(), colour: "#337ab7", ..money(dashboard.periods.iter().map(|earnings| earnings.totals.gross).collect()) },
+                                ChartSeries { name: "Net".to_string(), colour: "#5cb85c", ..money(dashboard.periods.iter().map(|earnings| earnings.totals.net).collect()) },
+                            ],
+                        }
+                        BarChart {
+                            title: "Hours worked",
+                            labels: labels.clone(),
+                            series: vec![ChartSeries::new_hours("Hours", "#f0ad4e", dashboard.periods.iter().map(|earnings| earnings.seconds).collect())],
+                        }
+                        BarChart {
+                            title: "Average hourly rate",
+                            labels: labels.clone(),
+                            series: vec![ChartSeries {
+                                name: "Rate".to_string(),
+                                colour: "#5bc0de",
+                                ..money(dashboard.periods.iter().map(|earnings| earnings.get_average_hourly_rate().unwrap_or(0)).collect())
+                            }],
+                        }
+                        BarChart {
+                            title: "Premium hours by type",
+                            labels: premium.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>(),
+                            series: vec![ChartSeries::new_hours("Hours", "#d9534f", premium.iter().map(|(_, seconds)| *seconds).collect())],
+                        }
+                    )
+                },
+                Err(e) => rsx!( p { class: "error", "Couldn't load the dashboard: {e}" } ),
+            }
+        }
+    )
+}
+
+// One bar per value and series, grouped by label.
+#[component]
+fn BarChart(title: String, labels: Vec<String>, series: Vec<ChartSeries>) -> Element {
+    let (width, height, axis) = (720.0_f32, 200.0_f32, 20.0_f32);
+    let max = series.iter().flat_map(|series| series.values.iter().copied()).fold(0.0_f32, f32::max);
+    let group_width = (width / labels.len().max(1) as f32).max(1.0);
+    let bar_width = (group_width * 0.8 / series.len().max(1) as f32).max(1.0);
+    // Only every so many labels fit under a year of weeks.
+    let label_step = (labels.len() / 13).max(1);
+
+    rsx!(
+        div { class: "chart",
+            h4 { "{title}" }
+            if max <= 0.0 {
+                p { "Nothing to show yet." }
+            } else {
+                svg {
+                    width: "{width}",
+                    height: "{height + axis}",
+                    view_box: "0 0 {width} {height + axis}",
+                    for (index, label) in labels.iter().enumerate() {
+                        for (number, series) in series.iter().enumerate() {
+                            {
+                                let value = series.values.get(index).copied().unwrap_or(0.0);
+                                let bar_height = value / max * (height - 10.0);
+                                rsx!(
+                                    rect {
+                                        x: "{index as f32 * group_width + group_width * 0.1 + number as f32 * bar_width}",
+                                        y: "{height - bar_height}",
+                                        width: "{bar_width}",
+                                        height: "{bar_height}",
+                                        fill: "{series.colour}",
+                                        title { {format!("{} {}: {}", label, series.name, series.display.get(index).cloned().unwrap_or_default())} }
+                                    }
+                                )
+                            }
+                        }
+                        if index % label_step == 0 {
+                            text {
+                                x: "{index as f32 * group_width + group_width / 2.0}",
+                                y: "{height + axis - 5.0}",
+                                text_anchor: "middle",
+                                font_size: "10",
+                                "{label}"
+                            }
+                        }
+                    }
+                }
+                div { class: "chart-legend",
+                    for series in series.iter() {
+                        span { style: "color: {series.colour}; margin-right: 12px;", "■ {series.name}" }
+                    }
+                }
+            }
+        }
+    )
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct ChartSeries {
+    name: String,
+    colour: &'static str,
+    values: Vec<f32>,
+    display: Vec<String>, // Each value as shown when hovered
+}
+impl ChartSeries {
+    fn new_money(values: Vec<u32>) -> ChartSeries {
+        ChartSeries {
+            name: String::new(),
+            colour: "#337ab7",
+            display: values.iter().map(|pence| format_pence(*pence)).collect(),
+            values: values.into_iter().map(|pence| pence as f32).collect(),
+        }
+    }
+    fn new_hours(name: &str, colour: &'static str, seconds: Vec<i64>) -> ChartSeries {
+        let hours: Vec<f32> = seconds.into_iter().map(|seconds| seconds as f32 / 3600.0).collect();
+        ChartSeries {
+            name: name.to_string(),
+            colour,
+            display: hours.iter().map(|hours| format!("{hours:.1}h")).collect(),
+            values: hours,
+        }
+    }
+}
+
+// Lists every job with create, edit and delete.
+#[component]
+fn JobsScreen() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let id_gen = use_context::<Arc<IdGenerator>>();
+    let mut jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let mut multipliers = use_context::<Signal<HashMap<i32, Vec<SalaryMultiplier>>>>();
+    let mut overrides = use_context::<Signal<HashMap<i32, Vec<ScheduleOverride>>>>();
+    let mut bank_holidays = use_context::<Signal<Vec<BankHoliday>>>();
+
+    let mut form = use_signal(|| None::<JobForm>);
+    let mut editing_id = use_signal(|| None::<i32>); // None = a new job
+    let mut confirm_delete = use_signal(|| None::<i32>);
+    let mut error = use_signal(|| None::<String>);
+
+    let save_db = db.clone();
+    let save = move |_| {
+        let Some(current) = form() else { return };
+        let job = match current.to_job() {
+            Ok(job) => job,
+            Err(message) => {
+                error.set(Some(message));
+                return
+            },
+        };
+        let job = Job { id: editing_id().unwrap_or_else(|| id_gen.next_job_id()), ..job };
+
+        match job.update(&save_db) {
+            Ok(()) => {
+                jobs.write().insert(job.id, job);
+                form.set(None);
+                error.set(None);
+            },
+            Err(e) => error.set(Some(format!("Couldn't save the job: {e}"))),
+        }
+    };
+
+    let mut sorted: Vec<Job> = jobs.read().values().cloned().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+    rsx!(
+        div { class: "jobs-screen",
+            h2 { "Jobs" }
+            button {
+                onclick: move |_| {
+                    editing_id.set(None);
+                    form.set(Some(JobForm::new()));
+                    error.set(None);
+                },
+                "New job"
+            }
+            table {
+                for job in sorted {
+                    tr { key: "{job.id}",
+                        td { "{job.name}" }
+                        td {
+                            match job.salary {
+                                Some(salary) => format!("{} a year", format_pence(salary.annual_salary)),
+                                None => format!("{} an hour", format_pence(job.basic_pay.max(0) as u32)),
+                            }
+                        }
+                        td {
+                            button {
+                                onclick: {
+                                    let job = job.clone();
+                                    move |_| {
+                                        editing_id.set(Some(job.id));
+                                        form.set(Some(JobForm::from_job(&job)));
+                                        error.set(None);
+                                    }
+                                },
+                                "Edit"
+                            }
+                            if confirm_delete() == Some(job.id) {
+                                "Delete {job.name} and all of its shifts? "
+                                button {
+                                    onclick: {
+                                        let db = db.clone();
+                                        let job = job.clone();
+                                        move |_| {
+                                            match job.delete_with_records(&db) {
+                                                Ok(()) => {
+                                                    jobs.write().remove(&job.id);
+                                                    multipliers.write().remove(&job.id);
+                                                    overrides.write().remove(&job.id);
+                                                    bank_holidays.write().retain(|holiday| holiday.job_id != Some(job.id));
+                                                    if editing_id() == Some(job.id) { form.set(None) }
+                                                },
+                                                Err(e) => error.set(Some(format!("Couldn't delete the job: {e}"))),
+                                            }
+                                            confirm_delete.set(None);
+                                        }
+                                    },
+                                    "Yes, delete"
+                                }
+                                button { onclick: move |_| confirm_delete.set(None), "Cancel" }
+                            } else {
+                                button { onclick: move |_| confirm_delete.set(Some(job.id)), "Delete" }
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some(error) = error() {
+                p { class: "error", "{error}" }
+            }
+            if let Some(current) = form() {
+                JobFormView { form }
+                button { onclick: save, if editing_id().is_some() { "Save changes" } else { "Create job" } }
+                button { onclick: move |_| { form.set(None); error.set(None); }, "Cancel" }
+                if !current.pattern.is_empty() && current.first_day.is_empty() {
+                    p { "Shift patterns count from the first day, remember to set it." }
+                }
+            }
+        }
+    )
+}
+
+#[component]
+fn JobFormView(form: Signal<Option<JobForm>>) -> Element {
+    let Some(current) = form() else { return rsx!() };
+    // Every input edits one field of the form in place.
+    let mut edit = move |change: &dyn Fn(&mut JobForm)| {
+        if let Some(form) = form.write().as_mut() { change(form) }
+    };
+
+    let weeks = if current.pattern == "fortnightly" { 2 } else { 1 };
+    let weekdays = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+    rsx!(
+        div { class: "job-form",
+            label { "Name " input { value: "{current.name}", oninput: move |e| edit(&|f| f.name = e.value()) } }
+            label { "Hourly rate (£) " input { value: "{current.basic_pay}", oninput: move |e| edit(&|f| f.basic_pay = e.value()) } }
+            label { "Daily base hours " input { r#type: "number", value: "{current.base_hours}", placeholder: "No overtime", oninput: move |e| edit(&|f| f.base_hours = e.value()) } }
+            label { "Tax week starts on "
+                select {
+                    onchange: move |e| edit(&|f| f.tax_week_start = e.value()),
+                    option { value: "sunday", selected: current.tax_week_start == "sunday", "Sunday" }
+                    option { value: "monday", selected: current.tax_week_start == "monday", "Monday" }
+                }
+            }
+            label { "Region "
+                select {
+                    onchange: move |e| edit(&|f| f.region = e.value()),
+                    for (value, name) in [("england", "England"), ("wales", "Wales"), ("scotland", "Scotland"), ("northern_ireland", "Northern Ireland")] {
+                        option { value: "{value}", selected: current.region == value, "{name}" }
+                    }
+                }
+            }
+
+            h4 { "Rota" }
+            label { "Shift pattern "
+                select {
+                    onchange: move |e| edit(&|f| f.pattern = e.value()),
+                    for (value, name) in [("", "None"), ("six_two", "6 on 2 off"), ("four_four", "4 on 4 off"), ("custom", "Weekly"), ("fortnightly", "Fortnightly"), ("rotation", "Rotation")] {
+                        option { value: "{value}", selected: current.pattern == value, "{name}" }
+                    }
+                }
+            }
+            label { "First day " input { r#type: "date", value: "{current.first_day}", oninput: move |e| edit(&|f| f.first_day = e.value()) } }
+            if current.pattern == "four_four" {
+                label {
+                    input { r#type: "checkbox", checked: current.is_paid_on_average, onchange: move |e| edit(&|f| f.is_paid_on_average = e.checked()) }
+                    "Paid on average"
+                }
+            }
+            if current.pattern == "rotation" {
+                label { "Runs " input { value: "{current.rotation}", placeholder: "2 on, 2 off, 3 on 14:00-22:00 Lates, 2 off", oninput: move |e| edit(&|f| f.rotation = e.value()) } }
+            }
+            if current.pattern == "custom" || current.pattern == "fortnightly" {
+                for week in 0..weeks {
+                    p { if weeks == 2 { "Week {week + 1}" } }
+                    for (day, weekday) in weekdays.iter().enumerate() {
+                        {
+                            let index = week * 7 + day;
+                            let custom_day = current.custom_days[index].clone();
+                            rsx!(
+                                div { key: "{index}",
+                                    label {
+                                        input { r#type: "checkbox", checked: custom_day.is_on, onchange: move |e| edit(&|f| f.custom_days[index].is_on = e.checked()) }
+                                        "{weekday}"
+                                    }
+                                    if custom_day.is_on {
+                                        input { r#type: "time", value: "{custom_day.start_time}", oninput: move |e| edit(&|f| f.custom_days[index].start_time = e.value()) }
+                                        input { r#type: "number", value: "{custom_day.minutes}", placeholder: "Minutes", oninput: move |e| edit(&|f| f.custom_days[index].minutes = e.value()) }
+                                    }
+                                }
+                            )
+                        }
+                    }
+                }
+            }
+            label { "Usual start " input { r#type: "time", value: "{current.fixed_start_time}", oninput: move |e| edit(&|f| f.fixed_start_time = e.value()) } }
+            label { "Usual length (minutes) " input { r#type: "number", value: "{current.fixed_shift_minutes}", oninput: move |e| edit(&|f| f.fixed_shift_minutes = e.value()) } }
+
+            h4 { "Unpaid breaks" }
+            for (index, (over_hours, break_minutes)) in current.break_rules.iter().cloned().enumerate() {
+                div { key: "{index}",
+                    "Over "
+                    input { r#type: "number", value: "{over_hours}", oninput: move |e| edit(&|f| f.break_rules[index].0 = e.value()) }
+                    " hours, "
+                    input { r#type: "number", value: "{break_minutes}", oninput: move |e| edit(&|f| f.break_rules[index].1 = e.value()) }
+                    " minutes unpaid "
+                    button { onclick: move |_| edit(&|f| { f.break_rules.remove(index); }), "Remove" }
+                }
+            }
+            button { onclick: move |_| edit(&|f| f.break_rules.push((String::new(), String::new()))), "Add break rule" }
+
+            h4 { "Clock rounding" }
+            for (is_clock_in, rule) in [(true, current.clock_in.clone()), (false, current.clock_out.clone())] {
+                div { key: "{is_clock_in}",
+                    if is_clock_in { "Clock in: " } else { "Clock out: " }
+                    input {
+                        r#type: "number", value: "{rule.interval_minutes}", placeholder: "Minutes",
+                        oninput: move |e| edit(&|f| f.get_rounding(is_clock_in).interval_minutes = e.value())
+                    }
+                    select {
+                        onchange: move |e| edit(&|f| f.get_rounding(is_clock_in).direction = e.value()),
+                        for direction in ["nearest", "up", "down", "exact"] {
+                            option { value: "{direction}", selected: rule.direction == direction, "{direction}" }
+                        }
+                    }
+                    input {
+                        r#type: "number", value: "{rule.grace_minutes}", placeholder: "Grace minutes",
+                        oninput: move |e| edit(&|f| f.get_rounding(is_clock_in).grace_minutes = e.value())
+                    }
+                }
+            }
+
+            h4 { "Salary" }
+            label { "Annual salary (£) " input { value: "{current.annual_salary}", placeholder: "Paid hourly", oninput: move |e| edit(&|f| f.annual_salary = e.value()) } }
+            if !current.annual_salary.is_empty() {
+                label { "Contracted hours a week " input { value: "{current.contracted_hours}", oninput: move |e| edit(&|f| f.contracted_hours = e.value()) } }
+                select {
+                    onchange: move |e| edit(&|f| f.pay_frequency = e.value()),
+                    for (value, name) in [("monthly", "Monthly"), ("four_weekly", "Four weekly"), ("weekly", "Weekly")] {
+                        option { value: "{value}", selected: current.pay_frequency == value, "{name}" }
+                    }
+                }
+                select {
+                    onchange: move |e| edit(&|f| f.proration = e.value()),
+                    option { value: "working_days", selected: current.proration == "working_days", "Pro-rata by working days" }
+                    option { value: "calendar_days", selected: current.proration == "calendar_days", "Pro-rata by calendar days" }
+                }
+                label { "Started " input { r#type: "date", value: "{current.salary_start}", oninput: move |e| edit(&|f| f.salary_start = e.value()) } }
+                label { "Left " input { r#type: "date", value: "{current.salary_end}", oninput: move |e| edit(&|f| f.salary_end = e.value()) } }
+            }
+
+            h4 { "Extras" }
+            label { "Split shift allowance (£) " input { value: "{current.split_amount}", oninput: move |e| edit(&|f| f.split_amount = e.value()) } }
+            label { "for gaps over (minutes) " input { r#type: "number", value: "{current.split_min_gap_minutes}", oninput: move |e| edit(&|f| f.split_min_gap_minutes = e.value()) } }
+            label {
+                input { r#type: "checkbox", checked: current.lieu_for_worked, onchange: move |e| edit(&|f| f.lieu_for_worked = e.checked()) }
+                "Day in lieu for working a bank holiday"
+            }
+            label {
+                input { r#type: "checkbox", checked: current.lieu_for_day_off, onchange: move |e| edit(&|f| f.lieu_for_day_off = e.checked()) }
+                "Day in lieu for a bank holiday on a day off"
+            }
+
+            h4 { "Payroll deductions" }
+            label { "Student loan "
+                select {
+                    onchange: move |e| edit(&|f| f.student_loan = e.value()),
+                    for (value, name) in [("", "None"), ("plan1", "Plan 1"), ("plan2", "Plan 2"), ("plan4", "Plan 4"), ("plan5", "Plan 5")] {
+                        option { value: "{value}", selected: current.student_loan == value, "{name}" }
+                    }
+                }
+            }
+            label {
+                input { r#type: "checkbox", checked: current.postgraduate_loan, onchange: move |e| edit(&|f| f.postgraduate_loan = e.checked()) }
+                "Postgraduate loan"
+            }
+            label { "Pension (%) " input { value: "{current.pension_percent}", placeholder: "No pension", oninput: move |e| edit(&|f| f.pension_percent = e.value()) } }
+            if !current.pension_percent.is_empty() {
+                select {
+                    onchange: move |e| edit(&|f| f.pension_method = e.value()),
+                    for (value, name) in [("net_pay", "Net pay"), ("relief_at_source", "Relief at source"), ("salary_sacrifice", "Salary sacrifice")] {
+                        option { value: "{value}", selected: current.pension_method == value, "{name}" }
+                    }
+                }
+                label {
+                    input { r#type: "checkbox", checked: current.pension_on_qualifying_earnings, onchange: move |e| edit(&|f| f.pension_on_qualifying_earnings = e.checked()) }
+                    "On qualifying earnings only"
+                }
+            }
+        }
+    )
+}
+
+// Inputs for every Job field as typed, checked and converted by to_job.
+#[derive(PartialEq, Debug, Clone, Default)]
+struct JobForm {
+    name: String,
+    basic_pay: String, // in pounds, e.g. 12.21
+    base_hours: String,
+    pattern: String, // "", "six_two", "four_four", "custom", "fortnightly" or "rotation"
+    is_paid_on_average: bool,
+    custom_days: Vec<CustomDayForm>, // Two weeks from Monday, the second only for fortnightly
+    rotation: String, // e.g. "2 on, 2 off, 3 on 14:00-22:00 Lates, 2 off"
+    first_day: String,
+    fixed_start_time: String,
+    fixed_shift_minutes: String,
+    tax_week_start: String,
+    region: String,
+    break_rules: Vec<(String, String)>, // Over hours, unpaid minutes
+    clock_in: RoundingForm,
+    clock_out: RoundingForm,
+    annual_salary: String, // Empty = paid hourly
+    contracted_hours: String,
+    pay_frequency: String,
+    proration: String,
+    salary_start: String,
+    salary_end: String,
+    split_amount: String,
+    split_min_gap_minutes: String,
+    lieu_for_worked: bool,
+    lieu_for_day_off: bool,
+    student_loan: String, // "", "plan1", "plan2", "plan4" or "plan5"
+    postgraduate_loan: bool,
+    pension_percent: String, // Empty = no pension
+    pension_method: String,
+    pension_on_qualifying_earnings: bool,
+}
+#[derive(PartialEq, Debug, Clone, Default)]
+struct CustomDayForm {
+    is_on: bool,
+    start_time: String,
+    minutes: String,
+}
+#[derive(PartialEq, Debug, Clone, Default)]
+struct RoundingForm {
+    interval_minutes: String, // Empty = not rounded
+    direction: String,
+    grace_minutes: String,
+}
+impl JobForm {
+    fn new() -> JobForm {
+        JobForm {
+            custom_days: vec![CustomDayForm::default(); 14],
+            tax_week_start: "sunday".to_string(),
+            region: "england".to_string(),
+            clock_in: RoundingForm { direction: "nearest".to_string(), ..Default::default() },
+            clock_out: RoundingForm { direction: "nearest".to_string(), ..Default::default() },
+            pay_frequency: "monthly".to_string(),
+            proration: "working_days".to_string(),
+            pension_method: "net_pay".to_string(),
+            pension_on_qualifying_earnings: true,
+            ..Default::default()
+        }
+    }
+
+    fn from_job(job: &Job) -> JobForm {
+        let mut form = JobForm::new();
+        let text = |value: Option<String>| value.unwrap_or_default();
+
+        form.name = job.name.clone();
+        form.basic_pay = format_pence(job.basic_pay.max(0) as u32).trim_start_matches('£').to_string();
+        form.base_hours = text(job.base_pay_period_hours.map(|hours| hours.to_string()));
+        form.first_day = text(job.first_day.map(|date| date.to_string()));
+        form.fixed_start_time = text(job.fixed_start_time.map(|time| time.format("%H:%M").to_string()));
+        form.fixed_shift_minutes = text(job.fixed_shift_duration.map(|duration| duration.num_minutes().to_string()));
+        form.tax_week_start = if job.get_tax_week_start() == TaxWeekStart::Monday { "monday" } else { "sunday" }.to_string();
+        form.region = match job.get_region() {
+            UKRegion::England => "england",
+            UKRegion::Wales => "wales",
+            UKRegion::Scotland => "scotland",
+            UKRegion::NorthernIreland => "northern_ireland",
+        }.to_string();
+
+        let mut set_custom_days = |week: usize, days: &[CustomDay]| {
+            for day in days {
+                let form_day = &mut form.custom_days[week * 7 + day.weekday.num_days_from_monday() as usize];
+                form_day.is_on = true;
+                if let Some(slot) = &day.slot {
+                    form_day.start_time = slot.start_time.format("%H:%M").to_string();
+                    form_day.minutes = slot.duration.num_minutes().to_string();
+                }
+            }
+        };
+        match &job.shift_pattern {
+            None => {},
+            Some(ShiftPattern::SixOnTwoOff) => form.pattern = "six_two".to_string(),
+            Some(ShiftPattern::FourOnFourOff(average)) => {
+                form.pattern = "four_four".to_string();
+                form.is_paid_on_average = average.is_paid_on_average;
+            },
+            Some(ShiftPattern::Custom(days)) => {
+                form.pattern = "custom".to_string();
+                set_custom_days(0, days);
+            },
+            Some(ShiftPattern::CustomFortnightly(week_one, week_two)) => {
+                form.pattern = "fortnightly".to_string();
+                set_custom_days(0, week_one);
+                set_custom_days(1, week_two);
+            },
+            Some(ShiftPattern::Rotation(runs)) => {
+                form.pattern = "rotation".to_string();
+                form.rotation = runs
+                    .iter()
+                    .map(|run| {
+                        let status = if run.status == ShiftStatus::ON { "on" } else { "off" };
+                        match &run.slot {
+                            Some(slot) => format!(
+                                "{} {status} {}-{}{}",
+                                run.days,
+                                slot.start_time.format("%H:%M"),
+                                (slot.start_time + slot.duration).format("%H:%M"),
+                                slot.label.as_ref().map(|label| format!(" {label}")).unwrap_or_default(),
+                            ),
+                            None => format!("{} {status}", run.days),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+            },
+        }
+
+        form.break_rules = job.break_rules
+            .iter()
+            .map(|rule| ((rule.min_shift_duration.num_minutes() as f32 / 60.0).to_string(), rule.unpaid_break.num_minutes().to_string()))
+            .collect();
+
+        let rounding_form = |rule: Option<RoundingRule>| match rule {
+            Some(rule) => RoundingForm {
+                interval_minutes: rule.interval_minutes.to_string(),
+                direction: format!("{:?}", rule.direction).to_lowercase(),
+                grace_minutes: rule.grace_minutes.to_string(),
+            },
+            None => RoundingForm { direction: "nearest".to_string(), ..Default::default() },
+        };
+        form.clock_in = rounding_form(job.clock_rounding.and_then(|rounding| rounding.clock_in));
+        form.clock_out = rounding_form(job.clock_rounding.and_then(|rounding| rounding.clock_out));
+
+        if let Some(salary) = job.salary {
+            form.annual_salary = format_pence(salary.annual_salary).trim_start_matches('£').to_string();
+            form.contracted_hours = (salary.contracted_minutes_per_week.get() as f32 / 60.0).to_string();
+            form.pay_frequency = match salary.frequency {
+                PayFrequency::Weekly => "weekly",
+                PayFrequency::FourWeekly => "four_weekly",
+                PayFrequency::Monthly => "monthly",
+            }.to_string();
+            form.proration = match salary.proration {
+                ProrationMethod::WorkingDays => "working_days",
+                ProrationMethod::CalendarDays => "calendar_days",
+            }.to_string();
+            form.salary_start = text(salary.start_date.map(|date| date.to_string()));
+            form.salary_end = text(salary.end_date.map(|date| date.to_string()));
+        }
+
+        if let Some(allowance) = job.split_shift_allowance {
+            form.split_amount = format_pence(allowance.amount).trim_start_matches('£').to_string();
+            form.split_min_gap_minutes = allowance.min_gap.num_minutes().to_string();
+        }
+        if let Some(policy) = job.lieu_policy {
+            form.lieu_for_worked = policy.for_worked_bank_holiday;
+            form.lieu_for_day_off = policy.for_bank_holiday_on_day_off;
+        }
+
+        for plan in &job.student_loans {
+            match plan {
+                StudentLoanPlan::Postgraduate => form.postgraduate_loan = true,
+                plan => form.student_loan = format!("{plan:?}").to_lowercase(),
+            }
+        }
+        if let Some(pension) = job.pension {
+            form.pension_percent = (pension.contribution as f32 / 10.0).to_string();
+            form.pension_method = match pension.method {
+                PensionMethod::NetPay => "net_pay",
+                PensionMethod::ReliefAtSource => "relief_at_source",
+                PensionMethod::SalarySacrifice => "salary_sacrifice",
+            }.to_string();
+            form.pension_on_qualifying_earnings = pension.is_on_qualifying_earnings;
+        }
+
+        form
+    }
+
+    fn get_rounding(&mut self, is_clock_in: bool) -> &mut RoundingForm {
+        if is_clock_in { &mut self.clock_in } else { &mut self.clock_out }
+    }
+
+    // The job with id 0, or a message saying which field is wrong.
+    fn to_job(&self) -> Result<Job, String> {
+        let name = self.name.trim();
+        if name.is_empty() { return Err("Enter a name for the job.".to_string()) }
+
+        let salary = self.get_salary()?;
+        let basic_pay = match (parse_pence(&self.basic_pay), &salary) {
+            (Some(pence), _) => pence as i32,
+            (None, Some(_)) if self.basic_pay.trim().is_empty() => 0,
+            (None, _) => return Err("Enter the hourly rate in pounds, e.g. 12.21".to_string()),
+        };
+        let base_pay_period_hours = match Self::parse_number::<u32>(&self.base_hours, "Daily base hours")? {
+            Some(hours) if !(1..=24).contains(&hours) => return Err("Daily base hours must be between 1 and 24.".to_string()),
+            hours => hours,
+        };
+
+        let first_day = Self::parse_date(&self.first_day, "First day")?;
+        let shift_pattern = self.get_shift_pattern()?;
+        if shift_pattern.is_some() && first_day.is_none() {
+            return Err("Choose the first day of the shift pattern.".to_string())
+        }
+
+        let fixed_start_time = Self::parse_time(&self.fixed_start_time, "Usual start")?;
+        let fixed_shift_duration = Self::parse_minutes(&self.fixed_shift_minutes, "Usual length")?;
+
+        let mut break_rules = Vec::new();
+        for (over_hours, break_minutes) in &self.break_rules {
+            let over = Self::parse_number::<f32>(over_hours, "Break rule hours")?;
+            let minutes = Self::parse_minutes(break_minutes, "Break rule minutes")?;
+            let (Some(over), Some(unpaid_break)) = (over, minutes) else {
+                return Err("Fill in both numbers for each break rule, or remove it.".to_string())
+            };
+            break_rules.push(BreakRule { min_shift_duration: Duration::minutes((over * 60.0).round() as i64), unpaid_break });
+        }
+
+        let clock_in = Self::get_rounding_rule(&self.clock_in, "Clock in")?;
+        let clock_out = Self::get_rounding_rule(&self.clock_out, "Clock out")?;
+        let clock_rounding = (clock_in.is_some() || clock_out.is_some()).then_some(ClockRounding { clock_in, clock_out });
+
+        let split_shift_allowance = match (self.split_amount.trim(), Self::parse_minutes(&self.split_min_gap_minutes, "Split shift gap")?) {
+            ("", None) => None,
+            (amount, Some(min_gap)) => match parse_pence(amount) {
+                Some(amount) => Some(SplitShiftAllowance { min_gap, amount }),
+                None => return Err("Enter the split shift allowance in pounds.".to_string()),
+            },
+            (_, None) => return Err("Enter how long a gap earns the split shift allowance.".to_string()),
+        };
+
+        let mut student_loans: Vec<StudentLoanPlan> = match self.student_loan.as_str() {
+            "plan1" => vec![StudentLoanPlan::Plan1],
+            "plan2" => vec![StudentLoanPlan::Plan2],
+            "plan4" => vec![StudentLoanPlan::Plan4],
+            "plan5" => vec![StudentLoanPlan::Plan5],
+            _ => Vec::new(),
+        };
+        if self.postgraduate_loan { student_loans.push(StudentLoanPlan::Postgraduate) }
+
+        let pension = match Self::parse_number::<f32>(&self.pension_percent, "Pension")? {
+            Some(percent) if !(0.0..=100.0).contains(&percent) => return Err("Pension must be between 0 and 100%.".to_string()),
+            Some(percent) => Some(Pension {
+                contribution: (percent * 10.0).round() as u32,
+                method: match self.pension_method.as_str() {
+                    "relief_at_source" => PensionMethod::ReliefAtSource,
+                    "salary_sacrifice" => PensionMethod::SalarySacrifice,
+                    _ => PensionMethod::NetPay,
+                },
+                is_on_qualifying_earnings: self.pension_on_qualifying_earnings,
+            }),
+            None => None,
+        };
+
+        Ok(Job {
+            id: 0,
+            name: name.to_string(),
+            basic_pay,
+            base_pay_period_hours,
+            shift_pattern,
+            first_day,
+            fixed_start_time,
+            fixed_shift_duration,
+            tax_week_start: Some(if self.tax_week_start == "monday" { TaxWeekStart::Monday } else { TaxWeekStart::Sunday }),
+            break_rules,
+            clock_rounding,
+            salary,
+            split_shift_allowance,
+            region: Some(match self.region.as_str() {
+                "wales" => UKRegion::Wales,
+                "scotland" => UKRegion::Scotland,
+                "northern_ireland" => UKRegion::NorthernIreland,
+                _ => UKRegion::England,
+            }),
+            lieu_policy: (self.lieu_for_worked || self.lieu_for_day_off).then_some(LieuPolicy {
+                for_worked_bank_holiday: self.lieu_for_worked,
+                for_bank_holiday_on_day_off: self.lieu_for_day_off,
+            }),
+            student_loans,
+            pension,
+        })
+    }
+
+    fn get_shift_pattern(&self) -> Result<Option<ShiftPattern>, String> {
+        let custom_week = |week: usize| -> Result<Vec<CustomDay>, String> {
+            let mut days = Vec::new();
+            for (day, form_day) in self.custom_days[week * 7..week * 7 + 7].iter().enumerate() {
+                if !form_day.is_on { continue }
+
+                let weekday = Weekday::try_from(day as u8).unwrap();
+                let start_time = Self::parse_time(&form_day.start_time, "Start time")?;
+                let duration = Self::parse_minutes(&form_day.minutes, "Minutes")?;
+                days.push(match (start_time, duration) {
+                    (Some(start_time), Some(duration)) => CustomDay::new(weekday).with_times(start_time, duration),
+                    (None, None) => CustomDay::new(weekday),
+                    _ => return Err(format!("Give {weekday} both a start time and a length, or neither.")),
+                });
+            }
+            Ok(days)
+        };
+
+        let pattern = match self.pattern.as_str() {
+            "six_two" => ShiftPattern::SixOnTwoOff,
+            "four_four" => ShiftPattern::FourOnFourOff(AveragePatternMatch { is_paid_on_average: self.is_paid_on_average }),
+            "custom" => ShiftPattern::Custom(custom_week(0)?),
+            "fortnightly" => ShiftPattern::CustomFortnightly(custom_week(0)?, custom_week(1)?),
+            "rotation" => ShiftPattern::Rotation(Self::parse_rotation(&self.rotation)?),
+            _ => return Ok(None),
+        };
+
+        let has_days_on = match &pattern {
+            ShiftPattern::Custom(days) => !days.is_empty(),
+            ShiftPattern::CustomFortnightly(week_one, week_two) => !week_one.is_empty() || !week_two.is_empty(),
+            _ => true,
+        };
+        if !has_days_on { return Err("Tick at least one day worked.".to_string()) }
+
+        Ok(Some(pattern))
+    }
+
+    // e.g. "2 on, 2 off, 3 on 14:00-22:00 Lates, 2 off", ON runs without times use the job's fixed times.
+    fn parse_rotation(text: &str) -> Result<Vec<RotationRun>, String> {
+        let error = || format!("Couldn't read the runs \"{text}\", write them like 2 on, 2 off, 3 on 14:00-22:00 Lates, 2 off");
+        let mut runs = Vec::new();
+
+        for part in text.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let words: Vec<&str> = part.split_whitespace().collect();
+            let [days, status, slot @ ..] = words.as_slice() else { return Err(error()) };
+            let days: u32 = days.parse().map_err(|_| error())?;
+            if days == 0 { return Err(error()) }
+
+            let run = match status.to_lowercase().as_str() {
+                "on" => RotationRun::on(days),
+                "off" => RotationRun::off(days),
+                _ => return Err(error()),
+            };
+            runs.push(match slot.split_first() {
+                None => run,
+                Some(_) if run.status == ShiftStatus::OFF => return Err(error()),
+                Some((times, label)) => {
+                    let (start, finish) = times.split_once('-').ok_or_else(error)?;
+                    let start_time = NaiveTime::parse_from_str(start, "%H:%M").map_err(|_| error())?;
+                    let finish_time = NaiveTime::parse_from_str(finish, "%H:%M").map_err(|_| error())?;
+                    // Finishing at or before the start means the shift runs past midnight.
+                    let mut duration = finish_time - start_time;
+                    if duration <= TimeDelta::zero() { duration += TimeDelta::days(1) }
+
+                    run.with_slot(ShiftSlot { start_time, duration, label: (!label.is_empty()).then(|| label.join(" ")) })
+                },
+            });
+        }
+
+        if !runs.iter().any(|run| run.status == ShiftStatus::ON) { return Err(error()) }
+        Ok(runs)
+    }
+
+    fn get_rounding_rule(form: &RoundingForm, field: &str) -> Result<Option<RoundingRule>, String> {
+        let Some(interval_minutes) = Self::parse_number::<u32>(&form.interval_minutes, field)? else { return Ok(None) };
+        if interval_minutes == 0 || interval_minutes > 60 {
+            return Err(format!("{field} rounding must be between 1 and 60 minutes."))
+        }
+
+        Ok(Some(RoundingRule {
+            interval_minutes,
+            direction: match form.direction.as_str() {
+                "up" => RoundingDirection::Up,
+                "down" => RoundingDirection::Down,
+                "exact" => RoundingDirection::Exact,
+                _ => RoundingDirection::Nearest,
+            },
+            grace_minutes: Self::parse_number(&form.grace_minutes, field)?.unwrap_or(0),
+        }))
+    }
+
+    fn get_salary(&self) -> Result<Option<Salary>, String> {
+        if self.annual_salary.trim().is_empty() { return Ok(None) }
+
+        let annual_salary = parse_pence(&self.annual_salary).ok_or("Enter the annual salary in pounds.")?;
+        let contracted_minutes = Self::parse_number::<f32>(&self.contracted_hours, "Contracted hours")?
+            .and_then(|hours| NonZeroU32::new((hours.max(0.0) * 60.0).round() as u32))
+            .ok_or("Enter the contracted hours a week.")?;
+        let start_date = Self::parse_date(&self.salary_start, "Salary start")?;
+        let end_date = Self::parse_date(&self.salary_end, "Salary end")?;
+        if let (Some(start), Some(end)) = (start_date, end_date) && end < start {
+            return Err("The salary can't end before it starts.".to_string())
+        }
+
+        Ok(Some(Salary {
+            annual_salary,
+            contracted_minutes_per_week: contracted_minutes,
+            frequency: match self.pay_frequency.as_str() {
+                "weekly" => PayFrequency::Weekly,
+                "four_weekly" => PayFrequency::FourWeekly,
+                _ => PayFrequency::Monthly,
+            },
+            proration: if self.proration == "calendar_days" { ProrationMethod::CalendarDays } else { ProrationMethod::WorkingDays },
+            start_date,
+            end_date,
+        }))
+    }
+
+    // Empty inputs are None, anything else has to parse.
+    fn parse_number<T: std::str::FromStr>(value: &str, field: &str) -> Result<Option<T>, String> {
+        let value = value.trim();
+        if value.is_empty() { return Ok(None) }
+        value.parse().map(Some).map_err(|_| format!("{field} isn't a valid number."))
+    }
+    fn parse_minutes(value: &str, field: &str) -> Result<Option<Duration>, String> {
+        match Self::parse_number::<i64>(value, field)? {
+            Some(minutes) if minutes <= 0 || minutes > 24 * 60 => Err(format!("{field} must be between 1 and 1440 minutes.")),
+            minutes => Ok(minutes.map(Duration::minutes)),
+        }
+    }
+    fn parse_date(value: &str, field: &str) -> Result<Option<NaiveDate>, String> {
+        if value.trim().is_empty() { return Ok(None) }
+        NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map(Some).map_err(|_| format!("{field} isn't a valid date."))
+    }
+    fn parse_time(value: &str, field: &str) -> Result<Option<NaiveTime>, String> {
+        if value.trim().is_empty() { return Ok(None) }
+        NaiveTime::parse_from_str(value.trim(), "%H:%M").map(Some).map_err(|_| format!("{field} isn't a valid time."))
+    }
+}
+
+// Reads an employer's .ics rota into shifts for a job, with a preview before saving.
+#[component]
+fn IcsImportView() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let id_gen = use_context::<Arc<IdGenerator>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let overrides = use_context::<Signal<HashMap<i32, Vec<ScheduleOverride>>>>();
+
+    const SHIFT_TYPES: [ShiftType; 6] = [
+        ShiftType::Scheduled,
+        ShiftType::Sick,
+        ShiftType::Holiday,
+        ShiftType::PaidLeave,
+        ShiftType::ExtraShift,
+        ShiftType::UnpaidAbsence,
+    ];
+
+    let mut job_id = use_signal(|| None::<i32>);
+    let mut path = use_signal(|| "rota.ics".to_string());
+    let mut rules = use_signal(IcsMappingRule::defaults);
+    let mut new_pattern = use_signal(String::new);
+    let mut new_type = use_signal(|| ShiftType::Sick);
+    let mut is_regex = use_signal(|| false);
+    let mut preview = use_signal(|| None::<IcsImportPreview>);
+    let mut message = use_signal(|| None::<String>);
+
+    let add_rule = move |_| {
+        let rule = if is_regex() {
+            IcsMappingRule::new(&new_pattern(), new_type())
+        } else {
+            IcsMappingRule::containing(&new_pattern(), new_type())
+        };
+        match rule {
+            // Added rules are checked before the defaults.
+            Ok(rule) => {
+                rules.write().insert(0, rule);
+                new_pattern.set(String::new());
+            },
+            Err(e) => message.set(Some(format!("Invalid pattern: {e}"))),
+        }
+    };
+
+    let preview_db = db.clone();
+    let load_preview = move |_| {
+        let Some(job) = job_id().and_then(|id| jobs.read().get(&id).cloned()) else {
+            message.set(Some("Choose a job first.".to_string()));
+            return
+        };
+        let contents = match std::fs::read_to_string(path()) {
+            Ok(contents) => contents,
+            Err(e) => {
+                message.set(Some(format!("Couldn't read {}: {e}", path())));
+                return
+            },
+        };
+        let job_overrides = overrides.read().get(&job.id).cloned().unwrap_or_default();
+
+        match IcsImportPreview::new(&preview_db, &job, &contents, &rules.read(), &job_overrides) {
+            Ok(loaded) => {
+                message.set(Some(format!("{} events found", loaded.rows.len())));
+                preview.set(Some(loaded));
+            },
+            Err(e) => message.set(Some(format!("Couldn't load shifts: {e}"))),
+        }
+    };
+
+    let import = move |_| {
+        let Some(loaded) = preview.take() else { return };
+        message.set(Some(match loaded.import(&db, &id_gen) {
+            Ok(count) => format!("Imported {count} shifts"),
+            Err(e) => format!("Couldn't import shifts: {e}"),
+        }));
+    };
+
+    rsx!(
+        div { class: "ics-import",
+            h3 { "Import from calendar" }
+            select {
+                onchange: move |e| { job_id.set(e.value().parse().ok()); preview.set(None); },
+                option { value: "", "Choose a job" }
+                for job in jobs.read().values() {
+                    option { value: "{job.id}", "{job.name}" }
+                }
+            }
+            input { value: "{path}", oninput: move |e| path.set(e.value()) }
+            button { onclick: load_preview, "Preview" }
+
+            h4 { "Rules" }
+            ul {
+                for (index, rule) in rules.read().iter().enumerate() {
+                    li { key: "{index}",
+                        "{rule.pattern} -> {rule.shift_type:?} "
+                        button { onclick: move |_| { rules.write().remove(index); }, "Remove" }
+                    }
+                }
+            }
+            input { value: "{new_pattern}", oninput: move |e| new_pattern.set(e.value()) }
+            select {
+                onchange: move |e| if let Some(shift_type) = e.value().parse::<usize>().ok().and_then(|i| SHIFT_TYPES.get(i)) { new_type.set(*shift_type) },
+                for (index, shift_type) in SHIFT_TYPES.iter().enumerate() {
+                    option { value: "{index}", selected: *shift_type == new_type(), "{shift_type:?}" }
+                }
+            }
+            label {
+                input { r#type: "checkbox", checked: is_regex(), onchange: move |e| is_regex.set(e.checked()) }
+                "Regex"
+            }
+            button { onclick: add_rule, "Add rule" }
+
+            if let Some(message) = message() {
+                p { "{message}" }
+            }
+            if let Some(loaded) = preview.read().as_ref() {
+                table {
+                    for row in loaded.rows.iter() {
+                        tr {
+                            td { "{row.date}" }
+                            td { "{row.start.time()} - {row.finish.time()}" }
+                            td { "{row.summary}" }
+                            td { "{row.shift_type:?}" }
+                            td {
+                                match row.status {
+                                    ImportStatus::New => "New".to_string(),
+                                    ImportStatus::Changed(id) => format!("Updates shift {id}"),
+                                    ImportStatus::Duplicate(id) => format!("Duplicate of shift {id}"),
+                                    ImportStatus::Skipped => "Skipped".to_string(),
+                                }
+                            }
+                            td { {row.warning.clone().unwrap_or_default()} }
+                        }
+                    }
+                }
+                button { onclick: import, "Import" }
+            }
+        }
+    )
+}
+
+// Writes the rota and logged shifts to an .ics file for calendar apps.
+#[component]
+fn IcsExportView() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let multipliers = use_context::<Signal<HashMap<i32, Vec<SalaryMultiplier>>>>();
+    let bank_holidays = use_context::<Signal<Vec<BankHoliday>>>();
+    let overrides = use_context::<Signal<HashMap<i32, Vec<ScheduleOverride>>>>();
+
+    let today = chrono::Local::now().date_naive();
+    let mut job_id = use_signal(|| None::<i32>); // None = all jobs
+    let mut from = use_signal(|| today - TimeDelta::weeks(4));
+    let mut to = use_signal(|| today + TimeDelta::weeks(12));
+    let mut path = use_signal(|| "rota.ics".to_string());
+    let mut message = use_signal(|| None::<String>);
+
+    let export = move |_| {
+        let jobs = jobs.read();
+        let selected: Vec<&Job> = jobs
+            .values()
+            .filter(|job| job_id().is_none_or(|id| id == job.id))
+            .collect();
+
+        let result = IcsExport::for_jobs(&db, &selected, &multipliers.read(), &overrides.read(), &bank_holidays.read(), from(), to());
+        message.set(Some(match result {
+            Ok(export) => match export.save(std::path::Path::new(&path())) {
+                Ok(()) => format!("Exported {} events to {}", export.events.len(), path()),
+                Err(e) => format!("Couldn't write {}: {e}", path()),
+            },
+            Err(e) => format!("Couldn't load shifts: {e}"),
+        }));
+    };
+
+    rsx!(
+        div { class: "ics-export",
+            h3 { "Export to calendar" }
+            select {
+                onchange: move |e| job_id.set(e.value().parse().ok()),
+                option { value: "", "All jobs" }
+                for job in jobs.read().values() {
+                    option { value: "{job.id}", "{job.name}" }
+                }
+            }
+            input { r#type: "date", value: "{from}", oninput: move |e| if let Ok(date) = NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d") { from.set(date) } }
+            input { r#type: "date", value: "{to}", oninput: move |e| if let Ok(date) = NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d") { to.set(date) } }
+            input { value: "{path}", oninput: move |e| path.set(e.value()) }
+            button { onclick: export, "Export .ics" }
+            if let Some(message) = message() {
+                p { "{message}" }
+            }
+        }
+    )
+}
 
+// Generates draft shifts from the rota and lets the user accept them.
+#[component]
+fn DraftShifts() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let id_gen = use_context::<Arc<IdGenerator>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let overrides = use_context::<Signal<HashMap<i32, Vec<ScheduleOverride>>>>();
+
+    let today = chrono::Local::now().date_naive();
+    let mut job_id = use_signal(|| None::<i32>);
+    let mut from = use_signal(|| today);
+    let mut to = use_signal(|| today + TimeDelta::weeks(4));
+    let mut message = use_signal(|| None::<String>);
+    let mut refresh = use_signal(|| 0);
+
+    let generate_db = db.clone();
+    let generate = move |_| {
+        let Some(job) = job_id().and_then(|id| jobs.read().get(&id).cloned()) else {
+            message.set(Some("Choose a job first.".to_string()));
+            return
+        };
+        let job_overrides = overrides.read().get(&job.id).cloned().unwrap_or_default();
+
+        let result = job.generate_draft_shifts(&generate_db, &id_gen, from(), to(), &job_overrides);
+        message.set(Some(match result {
+            Ok(generated) => format!(
+                "{} created, {} updated, {} removed, {} days already confirmed",
+                generated.created.len(), generated.updated.len(), generated.removed.len(), generated.skipped_confirmed
+            ),
+            Err(e) => format!("Couldn't generate shifts: {e}"),
+        }));
+        refresh += 1;
+    };
+
+    let _ = refresh();
+    let drafts: Vec<Shift> = job_id()
+        .and_then(|id| jobs.read().get(&id).cloned())
+        .and_then(|job| job.get_shifts_for_period_of(from(), to(), &db).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|shift| !shift.is_confirmed)
+        .collect();
 
-static MODELS: Lazy<Models> = Lazy::new(|| {
-    let mut models = Models::new();
-    models.define::<Job>().unwrap();
-    models.define::<Deduction>().unwrap();
-    models.define::<Shift>().unwrap();
-    models.define::<CustomShiftPaymentType>().unwrap();
-    models.define::<SalaryMultiplier>().unwrap();
-    models
-});
+    rsx!(
+        div { class: "draft-shifts",
+            h3 { "Generate shifts" }
+            select {
+                onchange: move |e| job_id.set(e.value().parse().ok()),
+                option { value: "", "Choose a job" }
+                for job in jobs.read().values() {
+                    option { value: "{job.id}", "{job.name}" }
+                }
+            }
+            input { r#type: "date", value: "{from}", oninput: move |e| if let Ok(date) = NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d") { from.set(date) } }
+            input { r#type: "date", value: "{to}", oninput: move |e| if let Ok(date) = NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d") { to.set(date) } }
+            button { onclick: generate, "Generate shifts" }
+            if let Some(message) = message() {
+                p { "{message}" }
+            }
+            ul {
+                for draft in drafts {
+                    li { key: "{draft.id}",
+                        "{draft.date}: {draft.start.time()} - {draft.finish.time()} (draft) "
+                        button {
+                            onclick: {
+                                let db = db.clone();
+                                move |_| {
+                                    let (start, finish) = (draft.start, draft.finish);
+                                    if draft.clone().confirmed(start, finish, &db).is_ok() {
+                                        refresh += 1;
+                                    }
+                                }
+                            },
+                            "Accept"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// Swaps, cancellations and extra rostered days for a job.
 #[component]
-fn App() -> Element {
-    let db = Arc::new(Builder::new().create_in_memory(&MODELS).expect("Error!"));
-    let jobs = use_signal(|| Job::load_all(&*db).expect("Error!"));
-    let salary_multipliers = use_signal(|| SalaryMultiplier::load_all(&*db).expect("Error!"));
+fn ScheduleOverrideEditor() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let id_gen = use_context::<Arc<IdGenerator>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let mut overrides = use_context::<Signal<HashMap<i32, Vec<ScheduleOverride>>>>();
+
+    let mut job_id = use_signal(|| None::<i32>);
+    let mut date = use_signal(|| None::<NaiveDate>);
+    let mut kind = use_signal(|| "off".to_string());
+    let mut other_date = use_signal(|| None::<NaiveDate>);
+    let mut start_time = use_signal(|| None::<NaiveTime>);
+    let mut duration_minutes = use_signal(|| None::<i64>);
+    let mut error = use_signal(|| None::<String>);
+
+    let save_db = db.clone();
+    let save = move |_| {
+        let (Some(job), Some(date)) = (job_id(), date()) else {
+            error.set(Some("Choose a job and a date.".to_string()));
+            return
+        };
+        let slot = start_time().zip(duration_minutes()).map(|(start_time, minutes)| ShiftSlot {
+            start_time,
+            duration: Duration::minutes(minutes),
+            label: None,
+        });
+        let change = match (kind().as_str(), other_date(), slot) {
+            ("off", _, _) => ScheduleChange::ForceOff,
+            ("on", _, slot) => ScheduleChange::ForceOn(slot),
+            ("swap", Some(other), _) => ScheduleChange::SwapWith(other),
+            ("times", _, Some(slot)) => ScheduleChange::ChangeTimes(slot),
+            ("swap", None, _) => { error.set(Some("Choose the date to swap with.".to_string())); return },
+            _ => { error.set(Some("Enter a start time and duration.".to_string())); return },
+        };
 
-    let id_gen = Arc::new(IdGenerator::new(&db).expect("Error!"));
-    
-    use_context_provider(|| db);
-    use_context_provider(|| id_gen);
-    use_context_provider(|| jobs);
-    use_context_provider(|| salary_multipliers);
+        let schedule_override = ScheduleOverride::new(&id_gen, job, date, change);
+        match schedule_override.save(&save_db) {
+            Ok(()) => {
+                overrides.write().entry(job).or_default().push(schedule_override);
+                error.set(None);
+            },
+            Err(e) => error.set(Some(format!("Couldn't save the change: {e}"))),
+        }
+    };
+
+    let job_overrides = job_id()
+        .and_then(|id| overrides.read().get(&id).cloned())
+        .unwrap_or_default();
 
     rsx!(
-        div { "Wages Calculator App!" }
-        div { class: "context-stats",
-            span { "Jobs: {jobs.read().len()}" }
-            span { " | " }
-            span { "Multipliers: {salary_multipliers.read().values().flatten().count()}" }
+        div { class: "schedule-overrides",
+            h3 { "Rota changes" }
+            select {
+                onchange: move |e| job_id.set(e.value().parse().ok()),
+                option { value: "", "Choose a job" }
+                for job in jobs.read().values() {
+                    option { value: "{job.id}", "{job.name}" }
+                }
+            }
+            input { r#type: "date", oninput: move |e| date.set(NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d").ok()) }
+            select {
+                onchange: move |e| kind.set(e.value()),
+                option { value: "off", "Stood down" }
+                option { value: "on", "Extra day" }
+                option { value: "swap", "Swap with" }
+                option { value: "times", "Change times" }
+            }
+            if kind() == "swap" {
+                input { r#type: "date", oninput: move |e| other_date.set(NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d").ok()) }
+            }
+            if kind() == "on" || kind() == "times" {
+                input { r#type: "time", oninput: move |e| start_time.set(NaiveTime::parse_from_str(&e.value(), "%H:%M").ok()) }
+                input { r#type: "number", placeholder: "Minutes", oninput: move |e| duration_minutes.set(e.value().parse().ok()) }
+            }
+            button { onclick: save, "Save" }
+            if let Some(error) = error() {
+                p { class: "error", "{error}" }
+            }
+            ul {
+                for schedule_override in job_overrides {
+                    li { key: "{schedule_override.id}",
+                        "{schedule_override.date}: {schedule_override.change:?} "
+                        button {
+                            onclick: {
+                                let db = db.clone();
+                                move |_| {
+                                    if schedule_override.delete(&db).is_ok()
+                                        && let Some(list) = overrides.write().get_mut(&schedule_override.job_id) {
+                                        list.retain(|o| o.id != schedule_override.id);
+                                    }
+                                }
+                            },
+                            "Remove"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// One-off and employer specific bank holidays, on top of the computed calendar.
+#[component]
+fn BankHolidayEditor() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let id_gen = use_context::<Arc<IdGenerator>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let mut bank_holidays = use_context::<Signal<Vec<BankHoliday>>>();
+
+    const REGIONS: [UKRegion; 4] = [UKRegion::England, UKRegion::Wales, UKRegion::Scotland, UKRegion::NorthernIreland];
+
+    let mut editing = use_signal(|| None::<BankHoliday>);
+    let mut date = use_signal(|| None::<NaiveDate>);
+    let mut name = use_signal(String::new);
+    let mut job_id = use_signal(|| None::<i32>); // None = every job
+    let mut region = use_signal(|| None::<UKRegion>); // None = every region
+    let mut is_observed = use_signal(|| true);
+    let mut error = use_signal(|| None::<String>);
+    let mut import_path = use_signal(|| "bank-holidays.json".to_string());
+    let mut import_report = use_signal(Vec::<String>::new);
+
+    let mut clear = move || {
+        editing.set(None);
+        date.set(None);
+        name.set(String::new());
+        job_id.set(None);
+        region.set(None);
+        is_observed.set(true);
+    };
+
+    let save_db = db.clone();
+    let import_id_gen = id_gen.clone();
+    let save = move |_| {
+        let Some(date) = date() else {
+            error.set(Some("Choose a date.".to_string()));
+            return
+        };
+        if name().trim().is_empty() {
+            error.set(Some("Enter a name.".to_string()));
+            return
+        }
+
+        let holiday = match editing() {
+            Some(existing) => BankHoliday { job_id: job_id(), region: region(), date, name: name().trim().to_string(), is_observed: is_observed(), ..existing },
+            None => BankHoliday::new_custom(&id_gen, job_id(), region(), date, name().trim().to_string(), is_observed()),
+        };
+        match holiday.update(&save_db) {
+            Ok(()) => {
+                let mut list = bank_holidays.write();
+                list.retain(|saved| saved.id != holiday.id);
+                list.push(holiday);
+                list.sort_by_key(|saved| saved.date);
+                error.set(None);
+                clear();
+            },
+            Err(e) => error.set(Some(format!("Couldn't save the bank holiday: {e}"))),
+        }
+    };
+
+    let import_db = db.clone();
+    let import = move |_| {
+        let result = std::fs::read_to_string(import_path())
+            .map_err(|e| e.into())
+            .and_then(|contents| BankHolidayImport::from_gov_uk_json(&import_db, &import_id_gen, &contents));
+
+        match result {
+            Ok(import) => {
+                let mut report = vec![format!(
+                    "{} added, {} removed, {} renamed",
+                    import.added.len(), import.removed.len(), import.renamed.len()
+                )];
+                report.extend(import.added.iter().map(|holiday| format!("Added {} {} ({:?})", holiday.date, holiday.name, holiday.region)));
+                report.extend(import.removed.iter().map(|holiday| format!("Removed {} {} ({:?})", holiday.date, holiday.name, holiday.region)));
+                report.extend(import.renamed.iter().map(|(old, holiday)| format!("Renamed {} from {old} to {} ({:?})", holiday.date, holiday.name, holiday.region)));
+                import_report.set(report);
+
+                if let Ok(saved) = BankHoliday::load_all(&import_db) {
+                    bank_holidays.set(saved);
+                }
+            },
+            Err(e) => import_report.set(vec![format!("Couldn't import {}: {e}", import_path())]),
+        }
+    };
+
+    rsx!(
+        div { class: "bank-holidays",
+            h3 { "Bank holidays" }
+            div { class: "bank-holiday-import",
+                input { value: "{import_path}", oninput: move |e| import_path.set(e.value()) }
+                button { onclick: import, "Import gov.uk file" }
+                ul {
+                    for line in import_report() {
+                        li { "{line}" }
+                    }
+                }
+            }
+            input {
+                r#type: "date",
+                value: date().map(|date| date.to_string()).unwrap_or_default(),
+                oninput: move |e| date.set(NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d").ok())
+            }
+            input { value: "{name}", placeholder: "Name", oninput: move |e| name.set(e.value()) }
+            select {
+                onchange: move |e| job_id.set(e.value().parse().ok()),
+                option { value: "", selected: job_id().is_none(), "All jobs" }
+                for job in jobs.read().values() {
+                    option { value: "{job.id}", selected: job_id() == Some(job.id), "{job.name}" }
+                }
+            }
+            select {
+                onchange: move |e| region.set(e.value().parse::<usize>().ok().and_then(|i| REGIONS.get(i).copied())),
+                option { value: "", selected: region().is_none(), "All regions" }
+                for (index, option_region) in REGIONS.iter().enumerate() {
+                    option { value: "{index}", selected: region() == Some(*option_region), "{option_region:?}" }
+                }
+            }
+            label {
+                input { r#type: "checkbox", checked: !is_observed(), onchange: move |e| is_observed.set(!e.checked()) }
+                "Worked as a normal day"
+            }
+            button { onclick: save, if editing().is_some() { "Save changes" } else { "Add" } }
+            if editing().is_some() {
+                button { onclick: move |_| clear(), "Cancel" }
+            }
+            if let Some(error) = error() {
+                p { class: "error", "{error}" }
+            }
+            ul {
+                for holiday in bank_holidays.read().iter().cloned() {
+                    li { key: "{holiday.id}",
+                        "{holiday.date}: {holiday.name}"
+                        if !holiday.is_observed { " (not observed)" }
+                        " "
+                        button {
+                            onclick: {
+                                let holiday = holiday.clone();
+                                move |_| {
+                                    date.set(Some(holiday.date));
+                                    name.set(holiday.name.clone());
+                                    job_id.set(holiday.job_id);
+                                    region.set(holiday.region);
+                                    is_observed.set(holiday.is_observed);
+                                    editing.set(Some(holiday.clone()));
+                                }
+                            },
+                            "Edit"
+                        }
+                        button {
+                            onclick: {
+                                let db = db.clone();
+                                move |_| {
+                                    if holiday.delete(&db).is_ok() {
+                                        bank_holidays.write().retain(|saved| saved.id != holiday.id);
+                                    }
+                                }
+                            },
+                            "Remove"
+                        }
+                    }
+                }
+            }
+        }
+    )
+}
+
+// Minutes gained or lost to clock rounding for each job this tax year.
+#[component]
+fn RoundingReportView() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+
+    let today = chrono::Local::now().date_naive();
+    let from = NaiveDate::from_ymd_opt(TaxWeek::get_year_cycle_of_financial_year(today), 4, 6).unwrap();
+
+    rsx!(
+        div { class: "rounding-report",
+            h3 { "Clock rounding this tax year" }
+            for job in jobs.read().values().filter(|job| job.clock_rounding.is_some()) {
+                match RoundingReport::new(&db, job, from, today) {
+                    Ok(report) => rsx!(
+                        p { "{job.name}: {report.minutes_gained} minutes over {report.shifts_rounded} shifts" }
+                    ),
+                    Err(e) => rsx!( p { "{job.name}: couldn't load shifts: {e}" } ),
+                }
+            }
+        }
+    )
+}
+
+// Tips, tronc and service charge paid through payroll, entered against a single shift.
+#[component]
+fn TipsEntry() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let id_gen = use_context::<Arc<IdGenerator>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+
+    const INCOME_TYPES: [(IncomeType, &str); 3] = [
+        (IncomeType::Tronc, "Tronc"),
+        (IncomeType::CardTips, "Card tips"),
+        (IncomeType::ServiceCharge, "Service charge"),
+    ];
+
+    let mut job_id = use_signal(|| None::<i32>);
+    let mut date = use_signal(|| None::<NaiveDate>);
+    let mut shift_id = use_signal(|| None::<i32>);
+    let mut income_type = use_signal(|| 0);
+    let mut amount = use_signal(String::new);
+    let mut is_ni_exempt = use_signal(|| false);
+    let mut message = use_signal(|| None::<String>);
+
+    let shifts: Vec<Shift> = match (job_id(), date()) {
+        (Some(job_id), Some(date)) => Shift::get_shifts_for_period(&db, date, date, Some(job_id)).unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    let save_shifts = shifts.clone();
+    let save = move |_| {
+        let Some(shift) = save_shifts.iter().find(|shift| Some(shift.id) == shift_id()) else {
+            message.set(Some("Choose the shift the tips are for.".to_string()));
+            return
+        };
+        let Some(pence) = parse_pence(&amount()).filter(|pence| *pence > 0) else {
+            message.set(Some("Enter the amount in pounds.".to_string()));
+            return
+        };
+
+        let (kind, name) = INCOME_TYPES[income_type()];
+        let tip = CustomShiftPaymentType::new_tip(&id_gen, shift, name.to_string(), kind, pence, is_ni_exempt());
+        match tip.save(&db) {
+            Ok(()) => {
+                message.set(Some(format!("Added {} {name}", format_pence(pence))));
+                amount.set(String::new());
+            },
+            Err(e) => message.set(Some(format!("Couldn't save the tips: {e}"))),
+        }
+    };
+
+    rsx!(
+        div { class: "tips-entry",
+            h3 { "Tips and tronc" }
+            select {
+                onchange: move |e| { job_id.set(e.value().parse().ok()); shift_id.set(None) },
+                option { value: "", "Choose a job" }
+                for job in jobs.read().values() {
+                    option { value: "{job.id}", "{job.name}" }
+                }
+            }
+            input {
+                r#type: "date",
+                oninput: move |e| { date.set(NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d").ok()); shift_id.set(None) }
+            }
+            select {
+                onchange: move |e| shift_id.set(e.value().parse().ok()),
+                option { value: "", selected: shift_id().is_none(), "Choose a shift" }
+                for shift in shifts.iter() {
+                    option { value: "{shift.id}", selected: shift_id() == Some(shift.id),
+                        {format!("{:?} {}-{}", shift.shift_type, shift.start.format("%H:%M"), shift.finish.format("%H:%M"))}
+                    }
+                }
+            }
+            select {
+                onchange: move |e| income_type.set(e.value().parse().unwrap_or(0)),
+                for (index, (_, name)) in INCOME_TYPES.iter().enumerate() {
+                    option { value: "{index}", selected: income_type() == index, "{name}" }
+                }
+            }
+            input { value: "{amount}", placeholder: "Amount", oninput: move |e| amount.set(e.value()) }
+            label {
+                input { r#type: "checkbox", checked: is_ni_exempt(), onchange: move |e| is_ni_exempt.set(e.checked()) }
+                "Paid by an independent troncmaster (no NI)"
+            }
+            button { onclick: save, "Add" }
+            if let Some(message) = message() {
+                p { "{message}" }
+            }
+        }
+    )
+}
+
+// Days in lieu earned and taken for each job this tax year.
+#[component]
+fn LieuDaysView() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
+    let overrides = use_context::<Signal<HashMap<i32, Vec<ScheduleOverride>>>>();
+    let mut refresh = use_signal(|| 0);
+
+    let today = chrono::Local::now().date_naive();
+    let from = NaiveDate::from_ymd_opt(TaxWeek::get_year_cycle_of_financial_year(today), 4, 6).unwrap();
+    let to = from + TimeDelta::days(364);
+    let _ = refresh();
+
+    rsx!(
+        div { class: "lieu-days",
+            h3 { "Days in lieu this tax year" }
+            for job in jobs.read().values().filter(|job| job.lieu_policy.is_some()) {
+                {
+                    let job_overrides = overrides.read().get(&job.id).cloned().unwrap_or_default();
+                    let balance = LieuBalance::new(&db, job, from, to, today, &job_overrides);
+                    let paid_leave: Vec<Shift> = job
+                        .get_shifts_for_period_of(from, to, &db)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|shift| shift.shift_type == ShiftType::PaidLeave)
+                        .collect();
+
+                    match balance {
+                        Ok(balance) => rsx!(
+                            h4 { "{job.name}: {balance.get_balance()} days" }
+                            ul {
+                                for accrual in balance.accrued {
+                                    li {
+                                        "{accrual.date}: {accrual.holiday} "
+                                        match accrual.reason {
+                                            LieuReason::WorkedBankHoliday => "(worked)",
+                                            LieuReason::BankHolidayOnDayOff => "(day off)",
+                                        }
+                                    }
+                                }
+                            }
+                            for shift in paid_leave {
+                                label { key: "{shift.id}",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: shift.is_lieu_day,
+                                        onchange: {
+                                            let db = db.clone();
+                                            let shift = shift.clone();
+                                            move |e: Event<FormData>| {
+                                                if shift.clone().with_lieu_day(e.checked()).updated(&db).is_ok() {
+                                                    refresh += 1;
+                                                }
+                                            }
+                                        }
+                                    }
+                                    "Paid leave on {shift.date} taken as a lieu day"
+                                }
+                            }
+                        ),
+                        Err(e) => rsx!( p { "{job.name}: couldn't load shifts: {e}" } ),
+                    }
+                }
+            }
         }
     )
+}
+
+// Lists Working Time Regulations breaches over the last reference period
+// and the upcoming rota.
+#[component]
+fn WorkingTimeReport() -> Element {
+    let db = use_context::<Arc<Database<'static>>>();
+    let jobs = use_context::<Signal<HashMap<i32, Job>>>();
 
+    let today = chrono::Local::now().date_naive();
+    let from = today - TimeDelta::weeks(WTR_REFERENCE_PERIOD_WEEKS);
+    let to = today + TimeDelta::weeks(4);
+
+    let breaches = WorkingTimeChecker::load(&db, &jobs.read().values().collect::<Vec<_>>(), from, to)
+        .map(|checker| checker.check(from, to));
+
+    rsx!(
+        div { class: "working-time-report",
+            h3 { "Working Time Regulations" }
+            match breaches {
+                Ok(breaches) if breaches.is_empty() => rsx!( p { "No breaches found." } ),
+                Ok(breaches) => rsx!(
+                    ul {
+                        for breach in breaches {
+                            li { "{breach.date()}: {breach.describe()}" }
+                        }
+                    }
+                ),
+                Err(e) => rsx!( p { "Couldn't check working time: {e}" } ),
+            }
+        }
+    )
 }
 
 
@@ -130,7 +3063,114 @@ impl TaxWeek {
 enum ShiftPattern {
     SixOnTwoOff,
     FourOnFourOff(AveragePatternMatch), // 
-    Custom(Vec<Weekday>),
+    Custom(Vec<CustomDay>),
+    // Week 1 starts on Job::first_day, then the two weeks alternate.
+    CustomFortnightly(Vec<CustomDay>, Vec<CustomDay>),
+    // ON/OFF runs repeated from Job::first_day, e.g. 3-on-3-off or
+    // 2-2-3 (Panama): ON 2, OFF 2, ON 3, OFF 2, ON 2, OFF 3.
+    Rotation(Vec<RotationRun>),
+}
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+struct RotationRun {
+    status: ShiftStatus,
+    days: u32,
+    // Times for the ON days of this run, if None the job's fixed times are used.
+    slot: Option<ShiftSlot>,
+}
+// e.g. week A earlies 06:00-14:00, week B lates 14:00-22:00, week C nights 22:00-06:00.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+struct ShiftSlot {
+    start_time: NaiveTime,
+    duration: Duration,
+    label: Option<String>,
+}
+impl RotationRun {
+    fn on(days: u32) -> RotationRun {
+        RotationRun { status: ShiftStatus::ON, days, slot: None }
+    }
+    fn off(days: u32) -> RotationRun {
+        RotationRun { status: ShiftStatus::OFF, days, slot: None }
+    }
+    fn with_slot(mut self, slot: ShiftSlot) -> RotationRun {
+        self.slot = Some(slot);
+        self
+    }
+}
+// e.g. Monday 09:00-17:00, Wednesday 12:00-20:00 and Saturday 08:00-13:00.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+struct CustomDay {
+    weekday: Weekday,
+    // If None the job's fixed times are used.
+    slot: Option<ShiftSlot>,
+}
+impl CustomDay {
+    fn new(weekday: Weekday) -> CustomDay {
+        CustomDay { weekday, slot: None }
+    }
+    fn with_times(mut self, start_time: NaiveTime, duration: Duration) -> CustomDay {
+        self.slot = Some(ShiftSlot { start_time, duration, label: None });
+        self
+    }
+}
+#[derive(PartialEq, Debug, Clone)]
+struct RotationDay {
+    status: ShiftStatus,
+    day_in_cycle: i32,
+    slot: Option<ShiftSlot>,
+}
+// A rotation expanded day by day from Job::first_day. The lead-in is worked once
+// before the cycle starts repeating (only SixOnTwoOff starting on a Sunday needs it).
+// day_in_cycle counts from 1 at the start of each ON block, through the OFF days after it.
+struct RotationCycle {
+    lead_in: Vec<RotationDay>,
+    cycle: Vec<RotationDay>,
+}
+impl RotationCycle {
+    fn new(lead_in: &[RotationRun], runs: &[RotationRun]) -> Option<RotationCycle> {
+        let cycle = Self::expand(runs);
+        if cycle.is_empty() { return None }
+
+        Some(RotationCycle { lead_in: Self::expand(lead_in), cycle })
+    }
+    fn expand(runs: &[RotationRun]) -> Vec<RotationDay> {
+        let mut days: Vec<RotationDay> = runs
+            .iter()
+            .flat_map(|run| std::iter::repeat_n(run, run.days as usize))
+            .map(|run| RotationDay {
+                status: run.status,
+                day_in_cycle: 0,
+                slot: run.slot.clone().filter(|_| run.status == ShiftStatus::ON),
+            })
+            .collect();
+
+        // Leading OFF days belong to the block that ends the cycle, so start counting
+        // from the first ON day and wrap around.
+        let first_on = days.iter().position(|day| day.status == ShiftStatus::ON).unwrap_or(0);
+        let mut day_in_cycle = 0;
+
+        for offset in 0..days.len() {
+            let index = (first_on + offset) % days.len();
+            let previous = days[(index + days.len() - 1) % days.len()].status;
+
+            day_in_cycle = if days[index].status == ShiftStatus::ON && (previous == ShiftStatus::OFF || offset == 0) {
+                1
+            } else {
+                day_in_cycle + 1
+            };
+            days[index].day_in_cycle = day_in_cycle;
+        }
+
+        days
+    }
+    // days_since_first_day starts at 0 on Job::first_day and is negative before it,
+    // in which case the cycle is projected backwards.
+    fn get_day(&self, days_since_first_day: i64) -> &RotationDay {
+        if let Ok(index) = usize::try_from(days_since_first_day) && let Some(day) = self.lead_in.get(index) {
+            return day
+        }
+        let position = (days_since_first_day - self.lead_in.len() as i64).rem_euclid(self.cycle.len() as i64);
+        &self.cycle[position as usize]
+    }
 }
 // If not paid on average,
 // Calculation should be proceeded by the weekly/monthly total amount of hours worked
@@ -141,10 +3181,10 @@ struct AveragePatternMatch {
 
 }
 impl ShiftPattern {
-    fn get_base_days_on(&self, date: NaiveDate, job: &Job) -> i32 {
-        match self {
+    fn get_base_days_on(&self, date: NaiveDate, job: &Job, overrides: &[ScheduleOverride]) -> i32 {
+        let base_days = match self {
             ShiftPattern::SixOnTwoOff => 5, // It is always 5 in this pattern
-            ShiftPattern::FourOnFourOff(_) => { // Shift vary, depending on the week
+            ShiftPattern::FourOnFourOff(_) | ShiftPattern::Rotation(_) | ShiftPattern::CustomFortnightly(..) => { // Shift vary, depending on the week
                 // Get the first day of the tax week,
                 // Find the last day (+6)
                 // Get the days working (only)
@@ -153,7 +3193,7 @@ impl ShiftPattern {
                 let first_day = tax_week.week_start_date;
                 let last_day = first_day + TimeDelta::days(6);
                 
-                let schedule  = job.get_scheduled_shifts_for_period(first_day, last_day)
+                let schedule  = job.get_scheduled_shifts_for_period(first_day, last_day, &[])
                 .into_iter()
                 .filter(|shift|{
                     shift.status == ShiftStatus::ON
@@ -163,37 +3203,308 @@ impl ShiftPattern {
                 schedule as i32
             },
             ShiftPattern::Custom(days) => {
-                days.into_iter().count() as i32
+                days.len() as i32
             }
-        }
+        };
+
+        // Days added or taken away by overrides in this tax week.
+        let week_start = TaxWeek::new(date, job.get_tax_week_start()).week_start_date;
+        let week_end = week_start + TimeDelta::days(6);
+        let count_on = |overrides: &[ScheduleOverride]| job
+            .scheduled_shifts(week_start, week_end, overrides)
+            .filter(|day| day.status == ShiftStatus::ON)
+            .count() as i32;
+
+        base_days + count_on(overrides) - count_on(&[])
     }
 }
 
 impl ShiftPattern {
-    fn get_custom_days(&self) -> Option<&Vec<Weekday>> {
+    // The days for the week containing date, weeks counted from first_day.
+    fn get_custom_days(&self, date: NaiveDate, first_day: NaiveDate) -> Option<&[CustomDay]> {
         match self {
-            ShiftPattern::Custom(v) => Some(v),
+            ShiftPattern::Custom(days) => Some(days),
+            ShiftPattern::CustomFortnightly(week_one, week_two) => {
+                let week = (date - first_day).num_days().div_euclid(7).rem_euclid(2);
+                Some(if week == 0 { week_one } else { week_two })
+            },
             _ => None,
         }
     }
+
+    // Every pattern except Custom weekdays is a rotation anchored at first_day.
+    fn get_rotation(&self, first_day: NaiveDate) -> Option<RotationCycle> {
+        match self {
+            ShiftPattern::SixOnTwoOff => {
+                let (lead_in, runs) = Self::get_six_on_two_off_runs(first_day);
+                RotationCycle::new(&lead_in, &runs)
+            },
+            ShiftPattern::FourOnFourOff(_) => RotationCycle::new(&[], &[RotationRun::on(4), RotationRun::off(4)]),
+            ShiftPattern::Rotation(runs) => RotationCycle::new(&[], runs),
+            ShiftPattern::Custom(_) | ShiftPattern::CustomFortnightly(..) => None,
+        }
+    }
+
+    // Blocks are 6 on 2 off, except a block starting on a Monday (5 on 3 off)
+    // and one starting on a Saturday (6 on 3 off). That makes a 7 week cycle
+    // Mon, Tue, Wed, Thu, Fri, Sat. A first block starting on a Sunday only happens once.
+    fn get_six_on_two_off_runs(first_day: NaiveDate) -> (Vec<RotationRun>, Vec<RotationRun>) {
+        let mut block_starts: Vec<Weekday> = Vec::new();
+        let mut blocks: Vec<[RotationRun; 2]> = Vec::new();
+        let mut block_start = first_day;
+
+        while !block_starts.contains(&block_start.weekday()) {
+            let block = match block_start.weekday() {
+                Weekday::Mon => [RotationRun::on(5), RotationRun::off(3)],
+                Weekday::Sat => [RotationRun::on(6), RotationRun::off(3)],
+                _ => [RotationRun::on(6), RotationRun::off(2)],
+            };
+
+            let length: i64 = block.iter().map(|run| run.days as i64).sum();
+
+            block_starts.push(block_start.weekday());
+            blocks.push(block);
+            block_start += TimeDelta::days(length);
+        }
+        let weekday = block_start.weekday();
+
+        let repeat_from = block_starts.iter().position(|start| *start == weekday).unwrap();
+        let lead_in = blocks[..repeat_from].iter().flatten().cloned().collect();
+        let runs = blocks[repeat_from..].iter().flatten().cloned().collect();
+
+        (lead_in, runs)
+    }
+}
+
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[native_model(id = 4, version = 2, from = JobV1)]
+#[native_db]
+struct Job {
+    #[primary_key]
+    id: i32,
+    name: String,
+    basic_pay: i32,
+    base_pay_period_hours: Option<u32>, // Daily, if None, don't calculate the overtime.
+    shift_pattern: Option<ShiftPattern>,
+    // The day marked as the beginning of the shift-pattern.
+    first_day: Option<NaiveDate>,
+    fixed_start_time: Option<NaiveTime>,
+    fixed_shift_duration: Option<Duration>,
+    tax_week_start: Option<TaxWeekStart>,
+    break_rules: Vec<BreakRule>, // Empty = breaks are paid
+    clock_rounding: Option<ClockRounding>, // None = paid to the exact second
+    salary: Option<Salary>, // None = paid hourly at basic_pay
+    split_shift_allowance: Option<SplitShiftAllowance>,
+    region: Option<UKRegion>, // Decides the bank holidays, None = England
+    lieu_policy: Option<LieuPolicy>, // None = no days in lieu
+    student_loans: Vec<StudentLoanPlan>,
+    pension: Option<Pension>,
+}
+// Job as saved before student loans and pensions, kept so older databases can be migrated.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[native_model(id = 4, version = 1)]
+#[native_db]
+struct JobV1 {
+    #[primary_key]
+    id: i32,
+    name: String,
+    basic_pay: i32,
+    base_pay_period_hours: Option<u32>,
+    shift_pattern: Option<ShiftPattern>,
+    first_day: Option<NaiveDate>,
+    fixed_start_time: Option<NaiveTime>,
+    fixed_shift_duration: Option<Duration>,
+    tax_week_start: Option<TaxWeekStart>,
+    break_rules: Vec<BreakRule>,
+    clock_rounding: Option<ClockRounding>,
+    salary: Option<Salary>,
+    split_shift_allowance: Option<SplitShiftAllowance>,
+    region: Option<UKRegion>,
+    lieu_policy: Option<LieuPolicy>,
+}
+impl From<JobV1> for Job {
+    fn from(job: JobV1) -> Self {
+        Job {
+            id: job.id,
+            name: job.name,
+            basic_pay: job.basic_pay,
+            base_pay_period_hours: job.base_pay_period_hours,
+            shift_pattern: job.shift_pattern,
+            first_day: job.first_day,
+            fixed_start_time: job.fixed_start_time,
+            fixed_shift_duration: job.fixed_shift_duration,
+            tax_week_start: job.tax_week_start,
+            break_rules: job.break_rules,
+            clock_rounding: job.clock_rounding,
+            salary: job.salary,
+            split_shift_allowance: job.split_shift_allowance,
+            region: job.region,
+            lieu_policy: job.lieu_policy,
+            student_loans: Vec::new(),
+            pension: None,
+        }
+    }
+}
+impl From<Job> for JobV1 {
+    fn from(job: Job) -> Self {
+        JobV1 {
+            id: job.id,
+            name: job.name,
+            basic_pay: job.basic_pay,
+            base_pay_period_hours: job.base_pay_period_hours,
+            shift_pattern: job.shift_pattern,
+            first_day: job.first_day,
+            fixed_start_time: job.fixed_start_time,
+            fixed_shift_duration: job.fixed_shift_duration,
+            tax_week_start: job.tax_week_start,
+            break_rules: job.break_rules,
+            clock_rounding: job.clock_rounding,
+            salary: job.salary,
+            split_shift_allowance: job.split_shift_allowance,
+            region: job.region,
+            lieu_policy: job.lieu_policy,
+        }
+    }
+}
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+struct Salary {
+    annual_salary: u32, // in pence
+    contracted_minutes_per_week: NonZeroU32, // e.g. 37.5 hours = 2250
+    frequency: PayFrequency,
+    proration: ProrationMethod,
+    // Starters and leavers are paid pro-rata for the part period.
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+}
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+enum PayFrequency {
+    Weekly,
+    FourWeekly,
+    Monthly,
+}
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+enum ProrationMethod {
+    WorkingDays, // Days ON in the shift pattern, Monday to Friday without one
+    CalendarDays,
+}
+// Repaid through payroll, a postgraduate loan can be repaid alongside one of the plans.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+enum StudentLoanPlan {
+    Plan1,
+    Plan2,
+    Plan4,
+    Plan5,
+    Postgraduate,
+}
+impl StudentLoanPlan {
+    // Yearly threshold in pence and percentage of the pay above it (2026/27).
+    fn get_threshold_and_rate(&self) -> (u32, u32) {
+        match self {
+            StudentLoanPlan::Plan1 => (2_690_000, 9),
+            StudentLoanPlan::Plan2 => (2_938_500, 9),
+            StudentLoanPlan::Plan4 => (3_379_500, 9),
+            StudentLoanPlan::Plan5 => (2_500_000, 9),
+            StudentLoanPlan::Postgraduate => (2_100_000, 6),
+        }
+    }
+    // Payroll rounds repayments down to whole pounds.
+    fn get_repayment(&self, niable_pay: u32, periods_in_year: u32) -> u32 {
+        let (threshold, rate) = self.get_threshold_and_rate();
+        let above = niable_pay.saturating_sub(threshold / periods_in_year);
+        above * rate / 100 / 100 * 100
+    }
+}
+// Employee pension contribution taken through payroll.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+struct Pension {
+    contribution: u32, // Per thousand of pay, e.g. 50 = 5%
+    method: PensionMethod,
+    // Auto-enrolment schemes only take it from pay between £6,240 and £50,270 a year.
+    is_on_qualifying_earnings: bool,
+}
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+enum PensionMethod {
+    NetPay, // Taken before tax
+    ReliefAtSource, // Taken after tax, less the 20% the provider claims back
+    SalarySacrifice, // Taken before tax and NI
+}
+impl Pension {
+    fn get_contribution(&self, pensionable_pay: u32, periods_in_year: u32) -> u32 {
+        let pay = if self.is_on_qualifying_earnings {
+            let (lower, upper) = (624_000 / periods_in_year, 5_027_000 / periods_in_year);
+            pensionable_pay.clamp(lower, upper) - lower
+        } else {
+            pensionable_pay
+        };
+        (pay as u64 * self.contribution as u64 / 1000) as u32
+    }
+}
+// When a bank holiday earns a day off in lieu.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+struct LieuPolicy {
+    for_worked_bank_holiday: bool,
+    for_bank_holiday_on_day_off: bool, // Rostered OFF on the rota
+}
+// Flat amount for a split shift whose longest gap is over min_gap, e.g. £5 over 2 hours.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+struct SplitShiftAllowance {
+    min_gap: Duration,
+    amount: u32, // in pence
+}
+// Unpaid break deducted once a shift is longer than min_shift_duration,
+// e.g. 30 minutes over 6 hours, 1 hour over 10 hours.
+// Only the longest break out of the matching rules is deducted.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+struct BreakRule {
+    min_shift_duration: Duration,
+    unpaid_break: Duration,
+}
+// How the employer rounds clock-ins and clock-outs before paying them.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+struct ClockRounding {
+    clock_in: Option<RoundingRule>,
+    clock_out: Option<RoundingRule>,
+}
+// e.g. nearest 15 minutes, or down to the quarter-hour with a 5 minute grace period.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+struct RoundingRule {
+    interval_minutes: u32,
+    direction: RoundingDirection,
+    // Within this many minutes of a boundary the time snaps to it, whatever the direction.
+    grace_minutes: u32,
 }
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+enum RoundingDirection {
+    Nearest,
+    Up,
+    Down,
+    Exact, // Only the grace period applies
+}
+impl RoundingRule {
+    fn apply(&self, time: NaiveDateTime) -> NaiveDateTime {
+        let interval = self.interval_minutes.max(1) as i64 * 60;
+        let grace = self.grace_minutes as i64 * 60;
+        let seconds = time.and_utc().timestamp();
+
+        let down = seconds - seconds.rem_euclid(interval);
+        let up = if down == seconds { down } else { down + interval };
+
+        let rounded = if seconds - down <= grace {
+            down
+        } else if up - seconds <= grace {
+            up
+        } else {
+            match self.direction {
+                RoundingDirection::Nearest => if seconds - down < up - seconds { down } else { up },
+                RoundingDirection::Up => up,
+                RoundingDirection::Down => down,
+                RoundingDirection::Exact => seconds,
+            }
+        };
 
-
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[native_model(id = 4, version = 1)]
-#[native_db]
-struct Job {
-    #[primary_key]
-    id: i32,
-    name: String,
-    basic_pay: i32,
-    base_pay_period_hours: Option<u32>, // Daily, if None, don't calculate the overtime.
-    shift_pattern: Option<ShiftPattern>,
-    // The day marked as the beginning of the shift-pattern.
-    first_day: Option<NaiveDate>,
-    fixed_start_time: Option<NaiveTime>,
-    fixed_shift_duration: Option<Duration>,
-    tax_week_start: Option<TaxWeekStart>,
+        time + TimeDelta::seconds(rounded - seconds)
+    }
 }
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[native_model(id = 8, version = 1)]
@@ -213,6 +3524,9 @@ struct SalaryMultiplier {
     schedule: ReocurrementSchedule,
     multiplier: Multiplier,
     time_window: Option<TimeWindow>,
+    // Applies to the seconds inside the period, both the schedule and the time window are ignored
+    // when it is set, e.g. 18:00 on 24 December to 06:00 on 27 December whatever the weekday.
+    premium_period: Option<PremiumPeriod>,
 }
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 struct Multiplier {
@@ -224,7 +3538,8 @@ impl Multiplier {
         self.value as f32 / 1000.0
     }
     fn from_floating_point(amount: f32) -> Self {
-        let value = (amount * 1000.0) as i32;
+        // Rounded, as 1.15 * 1000.0 is 1149.99... in f32
+        let value = (amount * 1000.0).round() as i32;
 
         Multiplier { value }
     }
@@ -249,6 +3564,10 @@ impl TimeWindowSummary {
     fn calculate_multiplied_amount(&self) -> f32 {
         self.seconds_worked as f32 * self.multiplier.multiplier.to_floating_point()
     }
+    // Only the extra on top of basic pay, e.g. 1.25 -> 0.25 of the seconds.
+    fn calculate_premium_amount(&self) -> f32 {
+        self.seconds_worked as f32 * (self.multiplier.multiplier.to_floating_point() - 1.0)
+    }
 }
 // Allow to select a given time window for a shift.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
@@ -258,14 +3577,24 @@ struct TimeWindow {
 }
 impl TimeWindow {
     // Get only multipliers if they can be applied for a given date
-    fn get_time_window_seconds_for(multipliers: HashMap<SalaryMultiplier, &Shift>) -> Vec<TimeWindowSummary> {
+    fn get_time_window_seconds_for(multipliers: HashMap<SalaryMultiplier, &Shift>, job: &Job) -> Vec<TimeWindowSummary> {
         let mut vec: Vec<TimeWindowSummary> = Vec::new();
 
         for (multiplier, shift) in multipliers {
-            // Time Window is always present as this function won't be called
-            // Without it.
-            let time_window = multiplier.time_window.unwrap();
-            let seconds_worked = Self::calculate_time_overlap_seconds(&time_window, shift.start, shift.finish);
+            // A window or period is always present as this function won't be called
+            // without one.
+            let overlap = |segments: &[ShiftSegment]| match (multiplier.premium_period, multiplier.time_window) {
+                (Some(period), _) => period.calculate_segments_overlap_seconds(segments),
+                (None, Some(time_window)) => time_window.calculate_segments_overlap_seconds(segments),
+                (None, None) => 0,
+            };
+            let shift_seconds = overlap(&shift.get_segments());
+            // Unpaid breaks inside the window don't earn the premium.
+            let break_seconds = shift
+                .get_unpaid_break_interval(job)
+                .map(|(start, finish)| overlap(&[ShiftSegment { start, finish }]))
+                .unwrap_or(0);
+            let seconds_worked = (shift_seconds - break_seconds).max(0);
             let time_window_summary = TimeWindowSummary { multiplier, seconds_worked };
 
             vec.push(time_window_summary);
@@ -273,6 +3602,13 @@ impl TimeWindow {
 
         vec
     }
+    // Overlap summed over every worked segment of a split shift.
+    fn calculate_segments_overlap_seconds(&self, segments: &[ShiftSegment]) -> i64 {
+        segments
+            .iter()
+            .map(|segment| self.calculate_time_overlap_seconds(segment.start, segment.finish))
+            .sum()
+    }
     fn calculate_time_overlap_seconds(
         &self,
         shift_start: NaiveDateTime,
@@ -336,6 +3672,44 @@ impl TimeWindow {
         total_seconds
     }
 }
+// A yearly period between two date-times, e.g. 24 December 18:00 to 27 December 06:00.
+// An end before the start (e.g. 31 December to 2 January) runs into the next year.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Copy)]
+struct PremiumPeriod {
+    start_month: u32,
+    start_day: u32,
+    start_time: NaiveTime,
+    end_month: u32,
+    end_day: u32,
+    end_time: NaiveTime,
+}
+impl PremiumPeriod {
+    // None if the dates don't exist that year, e.g. 29 February.
+    fn get_occurrence(&self, year: i32) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        let start = NaiveDate::from_ymd_opt(year, self.start_month, self.start_day)?.and_time(self.start_time);
+        let mut finish = NaiveDate::from_ymd_opt(year, self.end_month, self.end_day)?.and_time(self.end_time);
+        if finish <= start {
+            finish = NaiveDate::from_ymd_opt(year + 1, self.end_month, self.end_day)?.and_time(self.end_time);
+        }
+        Some((start, finish))
+    }
+    fn calculate_time_overlap_seconds(&self, start: NaiveDateTime, finish: NaiveDateTime) -> i64 {
+        (start.year() - 1..=finish.year())
+            .filter_map(|year| self.get_occurrence(year))
+            .map(|(period_start, period_finish)| {
+                let overlap_start = period_start.max(start);
+                let overlap_finish = period_finish.min(finish);
+                if overlap_finish > overlap_start { (overlap_finish - overlap_start).num_seconds() } else { 0 }
+            })
+            .sum()
+    }
+    fn calculate_segments_overlap_seconds(&self, segments: &[ShiftSegment]) -> i64 {
+        segments
+            .iter()
+            .map(|segment| self.calculate_time_overlap_seconds(segment.start, segment.finish))
+            .sum()
+    }
+}
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 enum MultiplierPriority {
     AlwaysApply,
@@ -374,14 +3748,15 @@ impl SalaryMultiplier {
         for multiplier in scan {
             multipliers
                 .entry(multiplier.job_id)
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(multiplier);
         }
 
         Ok(multipliers)
     }
+    // Priced on the seconds inside the window or period rather than the whole shift.
     fn is_time_window(&self) -> bool {
-        self.time_window.is_some()
+        self.time_window.is_some() || self.premium_period.is_some()
     }
 
     /*
@@ -392,6 +3767,7 @@ impl SalaryMultiplier {
      */
     fn get_modifiers<'a>(
     shift: &'a Shift,
+    job: &Job,
     multipliers: Vec<SalaryMultiplier>,
     ) -> MultiplierResult<'a> {        
         let (highest_modifiers, rest): (Vec<SalaryMultiplier>, Vec<SalaryMultiplier>) = multipliers
@@ -414,7 +3790,7 @@ impl SalaryMultiplier {
                 .filter(|m| m.priority == MultiplierPriority::AlwaysApply)
                 .collect();
 
-            let time_window_summaries = Self::get_time_window_multipliers(shift, &always_apply);
+            let time_window_summaries = Self::get_time_window_multipliers(shift, job, &always_apply);
 
             return MultiplierResult {
                 for_shift: shift,
@@ -424,7 +3800,7 @@ impl SalaryMultiplier {
             };
         }
 
-        let time_window_summaries = Self::get_time_window_multipliers(shift, &rest);
+        let time_window_summaries = Self::get_time_window_multipliers(shift, job, &rest);
 
         
         MultiplierResult {
@@ -434,43 +3810,22 @@ impl SalaryMultiplier {
             time_window_summary: time_window_summaries,
         }
     }
-    fn get_time_window_multipliers(shift: &Shift, multipliers: &[SalaryMultiplier]) -> Option<Vec<TimeWindowSummary>> {
+    fn get_time_window_multipliers(shift: &Shift, job: &Job, multipliers: &[SalaryMultiplier]) -> Option<Vec<TimeWindowSummary>> {
         let time_window_map: HashMap<SalaryMultiplier, &Shift> = multipliers
                 .iter()
                 .filter(|m| m.is_time_window())
                 .map(|m| (m.clone(), shift))
                 .collect();
 
-        Some(TimeWindow::get_time_window_seconds_for(time_window_map))
+        Some(TimeWindow::get_time_window_seconds_for(time_window_map, job))
     }
 
-    fn new(
-        id_gen: &IdGenerator,
-        job_id: i32,
-        name: String, 
-        description: Option<String>, 
-        schedule: ReocurrementSchedule, 
-        multiplier: Multiplier,
-        behavior: MultiplierBehavior,
-        priority: MultiplierPriority,
-        time_window: Option<TimeWindow>
-    ) -> SalaryMultiplier {
-
-            let id = id_gen.next_salary_multiplier_id();
-
-            let multiplier = SalaryMultiplier {
-                id: id,
-                job_id: job_id,
-                behavior: behavior,
-                name: name,
-                description: description,
-                schedule: schedule,
-                multiplier: multiplier,
-                time_window: time_window,
-                priority: priority,
-            };
-
-            multiplier
+    // Schedules are per date, so a period running over several days is checked against the shift itself.
+    fn applies_to(&self, shift: &Shift) -> bool {
+        match self.premium_period {
+            Some(period) => period.calculate_segments_overlap_seconds(&shift.get_segments()) > 0,
+            None => self.schedule.applies_on(shift.date),
+        }
     }
 }
 impl Job {
@@ -512,6 +3867,14 @@ impl Job {
             fixed_start_time: None,
             fixed_shift_duration: None,
             tax_week_start: None,
+            break_rules: Vec::new(),
+            clock_rounding: None,
+            salary: None,
+            split_shift_allowance: None,
+            region: None,
+            lieu_policy: None,
+            student_loans: Vec::new(),
+            pension: None,
         };
         let saved_job = job.saved(db).expect("Error occurred while performing a database operation"); // todo handle error properly
 
@@ -520,6 +3883,28 @@ impl Job {
         saved_job
     }
 
+    // Removes the job along with everything saved against it.
+    fn delete_with_records(&self, db: &Database) -> Result<(), Error> {
+        let rw = db.rw_transaction()?;
+
+        let shifts: Vec<Shift> = rw.scan().secondary(ShiftKey::job_id)?.start_with(self.id)?.collect::<Result<_, _>>()?;
+        for shift in shifts { rw.remove(shift)?; }
+        let deductions: Vec<Deduction> = rw.scan().secondary(DeductionKey::job_id)?.start_with(self.id)?.collect::<Result<_, _>>()?;
+        for deduction in deductions { rw.remove(deduction)?; }
+        let custom_payments: Vec<CustomShiftPaymentType> = rw.scan().secondary(CustomShiftPaymentTypeKey::job_id)?.start_with(self.id)?.collect::<Result<_, _>>()?;
+        for custom_payment in custom_payments { rw.remove(custom_payment)?; }
+        let multipliers: Vec<SalaryMultiplier> = rw.scan().secondary(SalaryMultiplierKey::job_id)?.start_with(self.id)?.collect::<Result<_, _>>()?;
+        for multiplier in multipliers { rw.remove(multiplier)?; }
+        let overrides: Vec<ScheduleOverride> = rw.scan().secondary(ScheduleOverrideKey::job_id)?.start_with(self.id)?.collect::<Result<_, _>>()?;
+        for schedule_override in overrides { rw.remove(schedule_override)?; }
+
+        let holidays: Vec<BankHoliday> = rw.scan().primary()?.all()?.collect::<Result<_, _>>()?;
+        for holiday in holidays.into_iter().filter(|holiday| holiday.job_id == Some(self.id)) { rw.remove(holiday)?; }
+
+        rw.remove(self.clone())?;
+        rw.commit()
+    }
+
     // Then update with each builder method
     fn with_shift_pattern(mut self, pattern: ShiftPattern, db: &Database) -> Self {
         self.shift_pattern = Some(pattern);
@@ -554,14 +3939,61 @@ impl Job {
         self.updated(db).expect("Error occurred while performing a database operation") // todo handle error properly
     }
 
+    fn with_break_rule(mut self, rule: BreakRule, db: &Database) -> Self {
+        self.break_rules.push(rule);
+        self.updated(db).expect("Error occurred while performing a database operation") // todo handle error properly
+    }
+
+    fn with_lieu_policy(mut self, policy: LieuPolicy, db: &Database) -> Self {
+        self.lieu_policy = Some(policy);
+        self.updated(db).expect("Error occurred while performing a database operation") // todo handle error properly
+    }
+
+    fn with_region(mut self, region: UKRegion, db: &Database) -> Self {
+        self.region = Some(region);
+        self.updated(db).expect("Error occurred while performing a database operation") // todo handle error properly
+    }
+
+    fn with_clock_rounding(mut self, rounding: ClockRounding, db: &Database) -> Self {
+        self.clock_rounding = Some(rounding);
+        self.updated(db).expect("Error occurred while performing a database operation") // todo handle error properly
+    }
+
+    fn with_salary(mut self, salary: Salary, db: &Database) -> Self {
+        self.salary = Some(salary);
+        self.updated(db).expect("Error occurred while performing a database operation") // todo handle error properly
+    }
+
+    fn with_split_shift_allowance(mut self, allowance: SplitShiftAllowance, db: &Database) -> Self {
+        self.split_shift_allowance = Some(allowance);
+        self.updated(db).expect("Error occurred while performing a database operation") // todo handle error properly
+    }
+
+    // Longest unpaid break out of the rules matching the time worked.
+    fn get_unpaid_break_for(&self, time_worked: TimeDelta) -> TimeDelta {
+        self.break_rules
+            .iter()
+            .filter(|rule| time_worked > rule.min_shift_duration)
+            .map(|rule| rule.unpaid_break)
+            .max()
+            .unwrap_or(TimeDelta::zero())
+    }
+
     // Example: basic_pay = 2500 (stored as pence, i.e., £25.00/hour)
     // Rate per second = 2500 / 3600 = 0.694 pence/second   
+    // Salaried jobs use the hourly rate derived from the salary instead.
     fn get_basic_hours_base_rate_per_second(&self) -> f32 {
-        self.basic_pay as f32 / 3600.0
+        match self.salary {
+            Some(salary) => salary.get_hourly_rate() / 3600.0,
+            None => self.basic_pay as f32 / 3600.0,
+        }
     }
     fn get_tax_week_start(&self) -> TaxWeekStart {
         self.tax_week_start.unwrap_or(TaxWeekStart::Sunday)
     }
+    fn get_region(&self) -> UKRegion {
+        self.region.unwrap_or(UKRegion::England)
+    }
     fn get_shifts_for_period_of(
         &self,
         start_date: NaiveDate,
@@ -571,192 +4003,354 @@ impl Job {
         // Get shifts for this specific job within the date range
         Shift::get_shifts_for_period(db, start_date, end_date, Some(self.id))
     }
-    
-    fn get_all_shifts(&self, db: &Database) -> Result<Vec<Shift>, db_type::Error> {
-        // Get entire work history for this job
-        Shift::get_all_shifts_for_job(db, self.id)
+    fn get_scheduled_shifts_for_period(&self, start_date: NaiveDate, end_date: NaiveDate, overrides: &[ScheduleOverride]) -> Vec<ScheduledShift> {
+        self.scheduled_shifts(start_date, end_date, overrides).collect()
     }
-    fn get_scheduled_shifts_for_period(&self, start_date: NaiveDate, end_date: NaiveDate) -> Vec<ScheduledShift> {
-        let shifts = self.calculate_scheduled_shifts_up_to(end_date);
-
-        shifts
-        .into_iter()
-        .filter(|day| day.date >= start_date && day.date <= end_date)
-        .collect()
+    fn get_scheduled_shifts_for_month(&self, target_month: u32, target_year: i32, overrides: &[ScheduleOverride]) -> Vec<ScheduledShift> {
+        let first_of_month = NaiveDate::from_ymd_opt(target_year, target_month, 1).expect("Couldn't find the date specified");
+        let last_of_month = first_of_month
+            .checked_add_months(chrono::Months::new(1))
+            .and_then(|date| date.pred_opt())
+            .expect("Couldn't find the date specified");
+
+        self.get_scheduled_shifts_for_period(first_of_month, last_of_month, overrides)
     }
-    fn get_scheduled_shifts_for_month(&self, target_month: u32, target_year: i32) -> Vec<ScheduledShift> {
-        let month = if(target_month + 1) == 13 { 12 } else { target_month } + 1;
-        let target_date = NaiveDate::from_ymd_opt(target_year, month, 1).expect("Couldn't find the date specified");
-
-        let schedule = self.calculate_scheduled_shifts_up_to(target_date);
-
-        let dates = schedule
-        .into_iter()
-        .filter(|date| date.date.month() == target_month && date.date.year() == target_year)
-        .collect();
-
-        dates
+    // Lazily walks the rota between two dates (inclusive), with overrides applied.
+    fn scheduled_shifts<'a>(&'a self, start_date: NaiveDate, end_date: NaiveDate, overrides: &'a [ScheduleOverride]) -> ScheduleIter<'a> {
+        ScheduleIter {
+            job: self,
+            rotation: self.get_rotation(),
+            overrides,
+            current: start_date,
+            end: end_date,
+        }
     }
-    fn calculate_scheduled_shifts_up_to(&self, target_date: NaiveDate) -> Vec<ScheduledShift> {
-        return match self.shift_pattern {
-            Some(ShiftPattern::SixOnTwoOff) => {
-                self.calculate_scheduled_shifts_for_six_on_two_off(target_date)
-            }
-            Some(ShiftPattern::FourOnFourOff(_)) => {
-                self.calculate_scheduled_shifts_for_four_on_four_off(target_date)
-            }
-            Some(ShiftPattern::Custom(_)) => {
-                self.calculate_scheduled_shifts_for_custom(target_date)
-            }
-            _ => {
-                Vec::new()
-            }
-        };
+    fn get_rotation(&self) -> Option<RotationCycle> {
+        self.shift_pattern.as_ref()?.get_rotation(self.first_day?)
     }
-    // Make sure to check if any days are selected!
-    fn calculate_scheduled_shifts_for_custom(&self, target_date: NaiveDate) -> Vec<ScheduledShift> {
-        let Some(first_day) = self.first_day else {
-            return Vec::new()
-        };
-        let Some(shift_pattern) = self.shift_pattern.as_ref() else {
-            return Vec::new()
+    // Works the day out from its position in the cycle, so it costs the same for any date.
+    // Dates before first_day project the pattern backwards.
+    fn get_scheduled_shift_on(&self, date: NaiveDate, overrides: &[ScheduleOverride]) -> Option<ScheduledShift> {
+        self.get_scheduled_shift_with(date, self.get_rotation().as_ref(), overrides)
+    }
+    fn get_scheduled_shift_with(&self, date: NaiveDate, rotation: Option<&RotationCycle>, overrides: &[ScheduleOverride]) -> Option<ScheduledShift> {
+        // Overrides apply even when the job has no pattern to fall back on.
+        let day_in_cycle = || self.get_pattern_day(date, rotation).map_or(0, |day| day.day_in_cycle);
+
+        let day = match ScheduleOverride::find_for(overrides, date) {
+            Some(ScheduleChange::ForceOn(slot)) => RotationDay { status: ShiftStatus::ON, day_in_cycle: day_in_cycle(), slot: slot.clone() },
+            Some(ScheduleChange::ForceOff) => RotationDay { status: ShiftStatus::OFF, day_in_cycle: day_in_cycle(), slot: None },
+            Some(ScheduleChange::ChangeTimes(slot)) => RotationDay { status: ShiftStatus::ON, day_in_cycle: day_in_cycle(), slot: Some(slot.clone()) },
+            Some(ScheduleChange::SwapWith(_)) | None => match ScheduleOverride::find_swap_for(overrides, date) {
+                Some(other) => self.get_pattern_day(other, rotation)?,
+                None => {
+                    let day = self.get_pattern_day(date, rotation)?;
+                    return Some(self.new_scheduled_shift(date, day.status, day.day_in_cycle, day.slot.as_ref()))
+                },
+            },
         };
 
-        let mut schedule: Vec<ScheduledShift> = Vec::new();
-
-        let mut current_day = ScheduledShift {
-            job_id: self.id,
-            date: first_day,
-            status: ShiftStatus::ON,
-            day_in_cycle: 0,
-        };
-        let days_working = shift_pattern.get_custom_days().expect("Error occurred while looking for Custom Weekdays.");
-
-        while current_day.date <= target_date {
-            schedule.push(current_day);
-
-            let next_date = current_day.date.succ_opt().unwrap();
-            let next_status = if !days_working.contains(&next_date.weekday()) { ShiftStatus::OFF} else { ShiftStatus::ON }; 
-            current_day = ScheduledShift {
-                job_id: self.id,
-                date: next_date,
-                status: next_status,
-                day_in_cycle: 0
-            };
+        let mut schedule = self.new_scheduled_shift(date, day.status, day.day_in_cycle, day.slot.as_ref());
+        schedule.is_overridden = true;
+        Some(schedule)
+    }
+    // What the pattern alone says about a date.
+    fn get_pattern_day(&self, date: NaiveDate, rotation: Option<&RotationCycle>) -> Option<RotationDay> {
+        let first_day = self.first_day?;
+
+        let pattern = self.shift_pattern.as_ref()?;
+        if let Some(days) = pattern.get_custom_days(date, first_day) {
+            let day = days.iter().find(|day| day.weekday == date.weekday());
+            return Some(RotationDay {
+                status: if day.is_some() { ShiftStatus::ON } else { ShiftStatus::OFF },
+                day_in_cycle: 0,
+                slot: day.and_then(|day| day.slot.clone()),
+            })
         }
 
-        schedule
+        match (pattern, rotation) {
+            (_, Some(rotation)) => Some(rotation.get_day((date - first_day).num_days()).clone()),
+            (_, None) => None,
+        }
     }
 
-    // Ask for the first day in the UI
-    fn calculate_scheduled_shifts_for_four_on_four_off(&self, target_date: NaiveDate) -> Vec<ScheduledShift> {
-        let Some(first_day) = self.first_day else { 
-            return Vec::new()
+    // ON days get expected times from the slot, falling back to the job's fixed times.
+    fn new_scheduled_shift(&self, date: NaiveDate, status: ShiftStatus, day_in_cycle: i32, slot: Option<&ShiftSlot>) -> ScheduledShift {
+        let times = match (status, slot) {
+            (ShiftStatus::OFF, _) => None,
+            (ShiftStatus::ON, Some(slot)) => Some((slot.start_time, slot.duration)),
+            (ShiftStatus::ON, None) => self.fixed_start_time.zip(self.fixed_shift_duration),
         };
+        let start = times.map(|(start_time, _)| date.and_time(start_time));
 
-        let mut schedule: Vec<ScheduledShift> = Vec::new();
-
-        let mut current_day = ScheduledShift {
+        ScheduledShift {
             job_id: self.id,
-            date: first_day,
-            status: ShiftStatus::ON,
-            day_in_cycle: 1,
-        };
+            date,
+            status,
+            day_in_cycle,
+            start,
+            finish: start.zip(times).map(|(start, (_, duration))| start + duration),
+            label: slot.and_then(|slot| slot.label.clone()),
+            is_overridden: false,
+        }
+    }
 
-        while current_day.date <= target_date {
-            schedule.push(current_day);
+    // Creates unconfirmed Scheduled shifts for ON days that have expected times.
+    // Re-running only refreshes or removes drafts, days with a confirmed shift are left alone.
+    fn generate_draft_shifts(
+        &self,
+        db: &Database,
+        id_gen: &IdGenerator,
+        from: NaiveDate,
+        to: NaiveDate,
+        overrides: &[ScheduleOverride],
+    ) -> Result<GeneratedShifts, Error> {
+        let existing = self.get_shifts_for_period_of(from, to, db)?;
+        let mut generated = GeneratedShifts::default();
 
-            let next_date = current_day.date.succ_opt().unwrap();
-            let next_day_in_cycle = if(current_day.day_in_cycle + 1) > 8 {
-                1
-            } else { current_day.day_in_cycle + 1 };
+        let rw = db.rw_transaction()?;
 
-            let next_status = if next_day_in_cycle > 4 {
-                ShiftStatus::OFF
-            } else { ShiftStatus::ON };
+        for day in self.scheduled_shifts(from, to, overrides) {
+            let on_day: Vec<&Shift> = existing.iter().filter(|shift| shift.date == day.date).collect();
 
-            current_day = ScheduledShift {
-                job_id: self.id,
-                date: next_date,
-                status: next_status,
-                day_in_cycle: next_day_in_cycle
+            if on_day.iter().any(|shift| shift.is_confirmed) {
+                generated.skipped_confirmed += 1;
+                continue
+            }
+            let draft = on_day.into_iter().find(|shift| shift.shift_type == ShiftType::Scheduled);
+
+            match (day.to_forecast_shift(), draft) {
+                (Some(expected), Some(draft)) => {
+                    if draft.start != expected.start || draft.finish != expected.finish {
+                        let mut updated = draft.clone();
+                        updated.start = expected.start;
+                        updated.finish = expected.finish;
+                        rw.upsert(updated.clone())?;
+                        generated.updated.push(updated);
+                    }
+                },
+                (Some(mut expected), None) => {
+                    expected.id = id_gen.next_shift_id();
+                    expected.is_confirmed = false;
+                    rw.insert(expected.clone())?;
+                    generated.created.push(expected);
+                },
+                // No longer rostered, e.g. after a rota change.
+                (None, Some(draft)) => {
+                    rw.remove(draft.clone())?;
+                    generated.removed.push(draft.clone());
+                },
+                (None, None) => {},
             }
         }
 
-        schedule
+        rw.commit()?;
+        Ok(generated)
     }
 
-    // Ask for the first day in the UI
-    fn calculate_scheduled_shifts_for_six_on_two_off(&self, target_date: NaiveDate) -> Vec<ScheduledShift> {
-         let Some(first_day) = self.first_day else { 
-            return Vec::new()
-        };
-
-        let mut schedule: Vec<ScheduledShift> = Vec::new();
+    // Days ON in the shift pattern, or Monday to Friday for jobs without one.
+    fn get_working_days(&self, start_date: NaiveDate, end_date: NaiveDate, overrides: &[ScheduleOverride]) -> Vec<NaiveDate> {
+        if self.shift_pattern.is_none() {
+            return start_date
+                .iter_days()
+                .take_while(|date| *date <= end_date)
+                .filter(|date| !matches!(date.weekday(), Weekday::Sat | Weekday::Sun))
+                .collect()
+        }
+        self.scheduled_shifts(start_date, end_date, overrides)
+            .filter(|day| day.status == ShiftStatus::ON)
+            .map(|day| day.date)
+            .collect()
+    }
 
-        let mut current_day = ScheduledShift {
-            job_id: self.id,
-            date: first_day,
-            status: ShiftStatus::ON,
-            day_in_cycle: 1,
-        };
+    fn is_working_on(&self, target_date: NaiveDate, overrides: &[ScheduleOverride]) -> bool {
+        self.get_scheduled_shift_on(target_date, overrides)
+        .map(|day| day.status == ShiftStatus::ON)
+        .unwrap_or(false)
+    }
+}
+impl Salary {
+    fn get_period_amount(&self) -> u32 {
+        match self.frequency {
+            PayFrequency::Weekly => self.annual_salary / 52,
+            PayFrequency::FourWeekly => self.annual_salary / 13,
+            PayFrequency::Monthly => self.annual_salary / 12,
+        }
+    }
+    // Pence per hour, used to pay overtime and premiums through the multipliers.
+    fn get_hourly_rate(&self) -> f32 {
+        let yearly_minutes = self.contracted_minutes_per_week.get() as f32 * 52.0;
+        self.annual_salary as f32 / (yearly_minutes / 60.0)
+    }
+    // The pay period a date falls into.
+    fn get_period_containing(&self, date: NaiveDate, job: &Job) -> (NaiveDate, NaiveDate) {
+        match self.frequency {
+            PayFrequency::Monthly => {
+                let start = date.with_day(1).unwrap();
+                let end = start.checked_add_months(chrono::Months::new(1)).unwrap().pred_opt().unwrap();
+                (start, end)
+            },
+            PayFrequency::Weekly => {
+                let start = TaxWeek::new(date, job.get_tax_week_start()).week_start_date;
+                (start, start + TimeDelta::days(6))
+            },
+            PayFrequency::FourWeekly => {
+                // Four-weekly periods run from tax weeks 1-4, 5-8 and so on.
+                let tax_week = TaxWeek::new(date, job.get_tax_week_start());
+                let weeks_into_period = (tax_week.week_commencing.max(1) as i64 - 1) % 4;
+                let start = tax_week.week_start_date - TimeDelta::weeks(weeks_into_period);
+                (start, start + TimeDelta::days(27))
+            },
+        }
+    }
+    fn is_employed_on(&self, date: NaiveDate) -> bool {
+        self.start_date.is_none_or(|start| date >= start) && self.end_date.is_none_or(|end| date <= end)
+    }
 
-        let mut current_block_start_weekday = first_day.weekday();
+    // Salary for every pay period overlapping [from, to], pro-rated where only part
+    // of a period is covered or employed, less a day's pay for each unpaid absence.
+    fn get_pay_for(&self, job: &Job, from: NaiveDate, to: NaiveDate, absences: &HashSet<NaiveDate>, overrides: &[ScheduleOverride]) -> u32 {
+        let mut total = 0u64;
+        let mut period_start = self.get_period_containing(from, job).0;
+
+        while period_start <= to {
+            let (_, period_end) = self.get_period_containing(period_start, job);
+
+            let calendar_days = || period_start.iter_days().take_while(|d| *d <= period_end).collect();
+            // A period without any days on, e.g. the week off on a 7 on 7 off rota, is still paid.
+            let counted_days: Vec<NaiveDate> = match self.proration {
+                ProrationMethod::CalendarDays => calendar_days(),
+                ProrationMethod::WorkingDays => Some(job.get_working_days(period_start, period_end, overrides))
+                    .filter(|days| !days.is_empty())
+                    .unwrap_or_else(calendar_days),
+            };
+            let paid_days = counted_days
+                .iter()
+                .filter(|date| **date >= from && **date <= to)
+                .filter(|date| self.is_employed_on(**date) && !absences.contains(*date))
+                .count() as u64;
 
-        while current_day.date <= target_date {
-            schedule.push(current_day);
+            total += self.get_period_amount() as u64 * paid_days / counted_days.len() as u64;
+            period_start = period_end.succ_opt().unwrap();
+        }
 
-            let next_date = current_day.date.succ_opt().unwrap();
-            let next_weekday = next_date.weekday();
+        total as u32
+    }
+}
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+struct ScheduledShift {
+    job_id: i32,
+    date: NaiveDate,
+    status: ShiftStatus,
+    day_in_cycle: i32,
+    // Expected times, None on OFF days or when the job has no fixed times.
+    start: Option<NaiveDateTime>,
+    finish: Option<NaiveDateTime>,
+    label: Option<String>, // e.g. "Earlies", "Nights"
+    is_overridden: bool, // Differs from the pattern because of a ScheduleOverride
+}
+// The rotation is expanded once up front, each day is then a lookup.
+struct ScheduleIter<'a> {
+    job: &'a Job,
+    rotation: Option<RotationCycle>,
+    overrides: &'a [ScheduleOverride],
+    current: NaiveDate,
+    end: NaiveDate,
+}
+impl Iterator for ScheduleIter<'_> {
+    type Item = ScheduledShift;
 
-            let cycle_limit = if current_block_start_weekday == Weekday::Sat { 9} else { 8 };
+    fn next(&mut self) -> Option<ScheduledShift> {
+        if self.current > self.end { return None }
 
-            let next_day_in_cycle = if(current_day.day_in_cycle + 1) > cycle_limit {
-                1
-            } else {
-                current_day.day_in_cycle + 1
-            };
-            if next_day_in_cycle == 1 { current_block_start_weekday = next_weekday }; 
+        let date = self.current;
+        self.current = date.succ_opt()?;
+        self.job.get_scheduled_shift_with(date, self.rotation.as_ref(), self.overrides)
+    }
+}
+impl ScheduledShift {
+    // An unsaved Shift with the expected times, used to price the rota before it's worked.
+    fn to_forecast_shift(&self) -> Option<Shift> {
+        if self.status != ShiftStatus::ON { return None }
+        Some(Shift::new(0, self.job_id, self.date, ShiftType::Scheduled, self.start?, self.finish?))
+    }
+}
+// Changes to the rota for a single day, layered over the shift pattern.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[native_model(id = 9, version = 1)]
+#[native_db]
+struct ScheduleOverride {
+    #[primary_key]
+    id: i32,
+    #[secondary_key]
+    job_id: i32,
+    date: NaiveDate,
+    change: ScheduleChange,
+}
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum ScheduleChange {
+    ForceOn(Option<ShiftSlot>), // e.g. rostered an extra day, None = the job's fixed times
+    ForceOff, // e.g. stood down
+    SwapWith(NaiveDate), // Swapped with a colleague, the two days trade places
+    ChangeTimes(ShiftSlot),
+}
+impl ScheduleOverride {
+    fn new(id_gen: &IdGenerator, job_id: i32, date: NaiveDate, change: ScheduleChange) -> ScheduleOverride {
+        ScheduleOverride {
+            id: id_gen.next_schedule_override_id(),
+            job_id,
+            date,
+            change,
+        }
+    }
+    fn load_all(db: &Database) -> Result<HashMap<i32, Vec<ScheduleOverride>>, Error> {
+        let r = db.r_transaction()?;
 
-            let next_status = if current_block_start_weekday == Weekday::Mon {
-                if next_day_in_cycle >= 6 {
-                    ShiftStatus::OFF
-                } else {
-                    ShiftStatus::ON
-                }
-            } else {
-                if next_day_in_cycle >= 7 {
-                    ShiftStatus::OFF
-                } else {
-                    ShiftStatus::ON
-                }
-            };
+        let scan: Vec<ScheduleOverride> = r
+            .scan()
+            .primary()?
+            .all()?
+            .collect::<Result<Vec<_>, _>>()?;
 
-            current_day = ScheduledShift {
-                job_id: self.id,
-                date: next_date,
-                status: next_status,
-                day_in_cycle: next_day_in_cycle
-            }
+        let mut overrides: HashMap<i32, Vec<ScheduleOverride>> = HashMap::new();
 
+        for schedule_override in scan {
+            overrides
+                .entry(schedule_override.job_id)
+                .or_default()
+                .push(schedule_override);
         }
-        schedule
+
+        Ok(overrides)
     }
+    fn get_for_job(db: &Database, job_id: i32) -> Result<Vec<ScheduleOverride>, Error> {
+        let r = db.r_transaction()?;
 
-    fn is_working_on(&self, target_date: NaiveDate, schedule: &[ScheduledShift]) -> bool {
-        schedule
-        .iter()
-        .find(|day| day.date == target_date)
-        .map(|day| day.status == ShiftStatus::ON)
-        .unwrap_or(false)
+        r.scan()
+            .secondary(ScheduleOverrideKey::job_id)?
+            .start_with(job_id)?
+            .collect()
+    }
+    // The latest override saved for a date wins.
+    fn find_for(overrides: &[ScheduleOverride], date: NaiveDate) -> Option<&ScheduleChange> {
+        overrides
+            .iter()
+            .filter(|o| o.date == date)
+            .max_by_key(|o| o.id)
+            .map(|o| &o.change)
+    }
+    // The date a day trades places with, from either side of the swap.
+    // A swap only counts while it is still the latest override on the date it was saved against.
+    fn find_swap_for(overrides: &[ScheduleOverride], date: NaiveDate) -> Option<NaiveDate> {
+        match Self::find_for(overrides, date) {
+            Some(ScheduleChange::SwapWith(other)) => Some(*other),
+            Some(_) => None,
+            None => overrides
+                .iter()
+                .map(|o| o.date)
+                .find(|other| Self::find_for(overrides, *other) == Some(&ScheduleChange::SwapWith(date))),
+        }
     }
-}
-#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
-struct ScheduledShift {
-    job_id: i32,
-    date: NaiveDate,
-    status: ShiftStatus,
-    day_in_cycle: i32
 }
 #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 enum ShiftStatus {
@@ -783,6 +4377,41 @@ struct Shift {
     shift_type: ShiftType,
     start: NaiveDateTime,
     finish: NaiveDateTime,
+    // Break actually taken, if None the job's break rules are used.
+    unpaid_break: Option<ShiftBreak>,
+    // Times as clocked, start and finish hold the rounded times once rounding is applied.
+    raw_start: Option<NaiveDateTime>,
+    raw_finish: Option<NaiveDateTime>,
+    // Drafts generated from the rota stay unconfirmed until the user accepts them.
+    is_confirmed: bool,
+    // UID of the calendar event this shift was imported from.
+    source_uid: Option<String>,
+    // Parts of a split shift, e.g. 07:00-11:00 and 16:00-21:00. Empty for a normal shift,
+    // start and finish always hold the first start and the last finish.
+    segments: Vec<ShiftSegment>,
+    // PaidLeave taken out of the lieu day balance.
+    is_lieu_day: bool,
+    // Clocked in and not yet out, finish is the same as start until then.
+    is_running: bool,
+}
+
+// One worked part of a split shift.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+struct ShiftSegment {
+    start: NaiveDateTime,
+    finish: NaiveDateTime,
+}
+impl ShiftSegment {
+    fn get_duration(&self) -> TimeDelta {
+        (self.finish - self.start).max(TimeDelta::zero())
+    }
+}
+
+// Without a start time the break is assumed to be taken in the middle of the shift.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+struct ShiftBreak {
+    start: Option<NaiveDateTime>,
+    duration: Duration,
 }
 
 // saved in the database
@@ -929,36 +4558,220 @@ impl Deduction {
         
         Ok(applicable)
     }
-}
 
-#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
-struct ShiftRecord {
-    shift: Shift,
-    deductions: Option<Vec<Deduction>>,
-    payment: Option<ShiftPayment>,
-}
+    // Number of days in the period the deduction is taken on.
+    fn get_occurrences(&self, start: NaiveDate, end: NaiveDate) -> u32 {
+        start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .filter(|date| self.schedule.applies_on(*date))
+            .count() as u32
+    }
+}
+
+// What a run of Job::generate_draft_shifts changed.
+#[derive(Default)]
+struct GeneratedShifts {
+    created: Vec<Shift>,
+    updated: Vec<Shift>,
+    removed: Vec<Shift>,
+    skipped_confirmed: usize,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+struct ShiftRecord {
+    shift: Shift,
+    deductions: Option<Vec<Deduction>>,
+    payment: Option<ShiftPayment>,
+}
+
+impl Shift {
+    fn date_to_key(date: NaiveDate) -> i32 {
+        date.year() * 10000 + date.month() as i32 *100 + date.day() as i32
+    }
+    fn new(
+        id: i32,
+        job_id: i32,
+        date: NaiveDate,
+        shift_type: ShiftType,
+        start: NaiveDateTime,
+        finish: NaiveDateTime,
+    ) -> Self {
+        Shift {
+            id,
+            job_id,
+            date,
+            date_key: Self::date_to_key(date),
+            shift_type,
+            start,
+            finish,
+            unpaid_break: None,
+            raw_start: None,
+            raw_finish: None,
+            is_confirmed: true,
+            source_uid: None,
+            segments: Vec::new(),
+            is_lieu_day: false,
+            is_running: false,
+        }
+    }
+
+    // Starts a shift now, as Scheduled on a rostered day and ExtraShift otherwise.
+    fn clock_in(db: &Database, id_gen: &IdGenerator, job: &Job, now: NaiveDateTime, overrides: &[ScheduleOverride]) -> Result<Self, Box<dyn std::error::Error>> {
+        if let Some(running) = Self::get_running(db)? {
+            return Err(format!("Already clocked in since {}", running.start.format("%H:%M")).into())
+        }
+
+        let shift_type = match job.get_scheduled_shift_on(now.date(), overrides) {
+            Some(day) if day.status == ShiftStatus::OFF => ShiftType::ExtraShift,
+            _ => ShiftType::Scheduled,
+        };
+        // The rota draft for the day becomes the clocked shift instead of staying alongside it.
+        let draft = Self::get_shifts_for_period(db, now.date(), now.date(), Some(job.id))?
+            .into_iter()
+            .find(|shift| !shift.is_confirmed);
+        let id = draft.as_ref().map_or_else(|| id_gen.next_shift_id(), |draft| draft.id);
+
+        let mut shift = Shift::new(id, job.id, now.date(), shift_type, now, now);
+        shift.raw_start = Some(now);
+        shift.is_running = true;
+
+        if let Some(other) = shift.find_overlap(db)? {
+            return Err(format!("{} is still going", other.describe()).into())
+        }
+        if draft.is_some() { shift.updated(db) } else { shift.saved(db) }
+    }
+
+    // Nothing is saved when the finished shift would overlap another one.
+    fn clock_out(mut self, job: &Job, now: NaiveDateTime, db: &Database) -> Result<Self, Box<dyn std::error::Error>> {
+        let finish = now.max(self.raw_start.unwrap_or(self.start));
+        self.finish = finish;
+        self.raw_finish = Some(finish);
+        self.is_running = false;
+
+        let shift = self.with_rounding(job);
+        if let Some(other) = shift.find_overlap(db)? {
+            return Err(format!("it would overlap {}", other.describe()).into())
+        }
+        shift.updated(db)
+    }
+
+    // There is at most one, clock_in refuses to start another.
+    fn get_running(db: &Database) -> Result<Option<Shift>, Error> {
+        let r = db.r_transaction()?;
+        for shift in r.scan().primary::<Shift>()?.all()? {
+            let shift = shift?;
+            if shift.is_running { return Ok(Some(shift)) }
+        }
+        Ok(None)
+    }
+
+    // Another shift on any job that is worked at the same time as this one.
+    fn find_overlap(&self, db: &Database) -> Result<Option<Shift>, Error> {
+        let from = self.date.pred_opt().unwrap_or(self.date);
+        let to = self.finish.date().succ_opt().unwrap_or(self.date);
+        let segments = self.get_segments();
+
+        Ok(Self::get_shifts_for_period(db, from, to, None)?
+            .into_iter()
+            .filter(|other| other.id != self.id)
+            .find(|other| other.get_segments().iter().any(|theirs| {
+                segments.iter().any(|ours| ours.start < theirs.finish && theirs.start < ours.finish)
+            })))
+    }
+
+    // Earliest of the job's shifts that day, a forecast shift that isn't saved counts
+    // as first when nothing saved starts before it.
+    fn is_first_on_date(&self, db: &Database) -> Result<bool, Error> {
+        Ok(!Self::get_shifts_for_period(db, self.date, self.date, Some(self.job_id))?
+            .iter()
+            .any(|other| other.id != self.id && (other.start, other.id) < (self.start, self.id)))
+    }
+
+    fn describe(&self) -> String {
+        format!("{:?} {} {}-{}", self.shift_type, self.date.format("%d/%m"), self.start.format("%H:%M"), self.finish.format("%H:%M"))
+    }
 
-impl Shift {
-    fn date_to_key(date: NaiveDate) -> i32 {
-        date.year() * 10000 + date.month() as i32 *100 + date.day() as i32
+    // Only PaidLeave can be taken as a lieu day.
+    fn with_lieu_day(mut self, is_lieu_day: bool) -> Self {
+        self.is_lieu_day = is_lieu_day && self.shift_type == ShiftType::PaidLeave;
+        self
     }
-    fn new(
-        id: i32,
-        job_id: i32,
-        date: NaiveDate,
-        shift_type: ShiftType,
-        start: NaiveDateTime,
-        finish: NaiveDateTime,
-    ) -> Self {
-        Shift {
-            id,
-            job_id,
-            date,
-            date_key: Self::date_to_key(date),
-            shift_type,
-            start,
-            finish,
+
+    fn with_segments(mut self, mut segments: Vec<ShiftSegment>) -> Self {
+        segments.sort_by_key(|segment| segment.start);
+
+        if let (Some(first), Some(last)) = (segments.first(), segments.last()) {
+            self.start = first.start;
+            self.finish = last.finish.max(first.start);
+        }
+        self.segments = if segments.len() > 1 { segments } else { Vec::new() };
+        self
+    }
+
+    fn get_segments(&self) -> Vec<ShiftSegment> {
+        if self.segments.is_empty() {
+            return vec![ShiftSegment { start: self.start, finish: self.finish }]
         }
+        self.segments.clone()
+    }
+
+    // Time off between the segments of a split shift.
+    fn get_gaps(&self) -> Vec<ShiftSegment> {
+        self.segments
+            .windows(2)
+            .filter(|pair| pair[1].start > pair[0].finish)
+            .map(|pair| ShiftSegment { start: pair[0].finish, finish: pair[1].start })
+            .collect()
+    }
+
+    fn get_longest_gap(&self) -> TimeDelta {
+        self.get_gaps().iter().map(ShiftSegment::get_duration).max().unwrap_or_default()
+    }
+
+    fn with_source_uid(mut self, uid: String) -> Self {
+        self.source_uid = (!uid.is_empty()).then_some(uid);
+        self
+    }
+
+    // Accepts a draft, with the times actually worked if they differ.
+    fn confirmed(mut self, start: NaiveDateTime, finish: NaiveDateTime, db: &Database) -> Result<Self, Box<dyn std::error::Error>> {
+        self.start = start;
+        self.finish = finish;
+        self.is_confirmed = true;
+        self.updated(db)
+    }
+
+    // Rounds start and finish by the job's rules, always from the raw clocked times
+    // so it is safe to call more than once.
+    // Only worked shifts are clocked, leave and sickness keep their times.
+    fn with_rounding(mut self, job: &Job) -> Self {
+        let Some(rounding) = job.clock_rounding else { return self };
+        if !self.shift_type.is_worked() { return self }
+
+        let raw_start = self.raw_start.unwrap_or(self.start);
+        let raw_finish = self.raw_finish.unwrap_or(self.finish);
+
+        self.start = rounding.clock_in.map_or(raw_start, |rule| rule.apply(raw_start));
+        self.finish = rounding.clock_out.map_or(raw_finish, |rule| rule.apply(raw_finish)).max(self.start);
+        self.raw_start = Some(raw_start);
+        self.raw_finish = Some(raw_finish);
+
+        // Only the first clock-in and the last clock-out of a split shift are rounded.
+        if let Some(first) = self.segments.first_mut() {
+            first.start = self.start;
+        }
+        if let Some(last) = self.segments.last_mut() {
+            last.finish = self.finish.max(last.start);
+        }
+
+        self
+    }
+
+    fn get_raw_time_worked(&self) -> TimeDelta {
+        self.get_time_worked()
+            + (self.start - self.raw_start.unwrap_or(self.start))
+            + (self.raw_finish.unwrap_or(self.finish) - self.finish)
     }
 
     // Gets all shifts for a given period and then filters by job_id.
@@ -989,38 +4802,7 @@ impl Shift {
         
         Ok(filtered)
     }
-    
-    // Needed for entire history
-    fn get_all_shifts_for_job(
-        db: &Database,
-        job_id: i32,
-    ) -> Result<Vec<Shift>, db_type::Error> {
-        let r = db.r_transaction()?;
-        
-        let shifts: Result<Vec<Shift>, db_type::Error> = r
-            .scan()
-            .secondary(ShiftKey::job_id)?
-            .start_with(job_id)?
-            .collect();
-        
-        shifts
-    }
-    // Get all shifts for a specific date
-    fn get_shifts_for_date(
-        db: &Database,
-        date: NaiveDate
-    ) -> Result<Vec<Shift>, db_type::Error> {
-        let r = db.r_transaction()?;
-        let date_key = Self::date_to_key(date);
-        
-        let shifts: Result<Vec<Shift>, db_type::Error> = r
-            .scan()
-            .secondary(ShiftKey::date_key)?
-            .start_with(date_key)?
-            .collect();
-        
-        shifts
-    }
+
     fn get_pretty_time_worked(&self) -> String {
         let time = self.get_time_worked();
 
@@ -1031,9 +4813,43 @@ impl Shift {
         format!("{}h, {}m, {}s", h, m, s)
     }
 
+    // Summed over the segments, so the gaps in a split shift aren't counted.
     fn get_time_worked(&self) -> TimeDelta {
-        self.finish.signed_duration_since(self.start)
+        self.get_segments().iter().map(ShiftSegment::get_duration).sum()
+    }
+
+    // Gaps between segments count towards the break the job's rules ask for.
+    fn get_unpaid_break(&self, job: &Job) -> TimeDelta {
+        match self.unpaid_break {
+            Some(recorded) => recorded.duration,
+            None => {
+                let gaps: TimeDelta = self.get_gaps().iter().map(ShiftSegment::get_duration).sum();
+                (job.get_unpaid_break_for(self.get_time_worked()) - gaps).max(TimeDelta::zero())
+            },
+        }
+    }
+
+    // Where the unpaid break sits within the shift, None if there isn't one.
+    // Without a recorded start it goes in the middle of the longest segment.
+    fn get_unpaid_break_interval(&self, job: &Job) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        let segments = self.get_segments();
+        let longest = segments.iter().max_by_key(|segment| segment.get_duration())?;
+
+        let duration = self.get_unpaid_break(job).min(longest.get_duration());
+        if duration <= TimeDelta::zero() { return None }
 
+        let start = self.unpaid_break
+            .and_then(|recorded| recorded.start)
+            .unwrap_or(longest.start + (longest.get_duration() - duration) / 2)
+            .max(self.start);
+        let finish = (start + duration).min(self.finish);
+
+        Some((start, finish))
+    }
+
+    // Time worked net of unpaid breaks, this is what gets paid.
+    fn get_paid_time(&self, job: &Job) -> TimeDelta {
+        (self.get_time_worked() - self.get_unpaid_break(job)).max(TimeDelta::zero())
     }
 }
 #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
@@ -1042,18 +4858,39 @@ enum ShiftType {
     Sick,
     Holiday,
     PaidLeave,
-    ExtraShift
+    ExtraShift,
+    UnpaidAbsence, // Only affects salaried jobs, which lose a day's pay
+}
+impl ShiftType {
+    // Time actually spent at work, as opposed to leave, sickness or absence.
+    fn is_worked(&self) -> bool {
+        matches!(self, ShiftType::Scheduled | ShiftType::ExtraShift)
+    }
+    fn all() -> [ShiftType; 6] {
+        [ShiftType::Scheduled, ShiftType::ExtraShift, ShiftType::Sick, ShiftType::Holiday, ShiftType::PaidLeave, ShiftType::UnpaidAbsence]
+    }
+    // Text colour for the calendar.
+    fn get_colour(&self) -> &'static str {
+        match self {
+            ShiftType::Scheduled => "#2e6da4",
+            ShiftType::ExtraShift => "#8a6d3b",
+            ShiftType::Sick => "#c9302c",
+            ShiftType::Holiday | ShiftType::PaidLeave => "#3c763d",
+            ShiftType::UnpaidAbsence => "#777777",
+        }
+    }
 }
 
 // Shift Pay is generated automatically, no need to save in the database!
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 
 struct ShiftPayment {
-    shift_id: i32,
+    shift_id: Option<i32>, // None for pay not tied to a shift, e.g. salary
     job_id: i32,
     amount: u32,
     payment_type: ShiftPaymentType,
     deductions: Option<Vec<Deduction>>, // Only applicable, if set up by the user
+    seconds: i64, // Paid time the amount is for, 0 for fixed amounts
 }
 
 impl ShiftPayment {
@@ -1061,68 +4898,334 @@ impl ShiftPayment {
     // Multipliers should be taken from the global_context!
     // Cannot be here as it's not a component.
     // let Some(multipliers) = SalaryMultiplier::get_for(job, multipliers) else { return Vec::new() };
-    fn new_for_shift(shift: &Shift, job: &Job, db: &Database, multipliers: Vec<SalaryMultiplier>) -> Vec<ShiftPayment> {
+    // Holidays are built once by the caller for the whole period being priced.
+    fn new_for_shift(
+        shift: &Shift,
+        job: &Job,
+        db: &Database,
+        multipliers: Vec<SalaryMultiplier>,
+        holidays: &BankHolidayChecker,
+    ) -> Result<Vec<ShiftPayment>, Error> {
         let mut payments = Vec::new();
+        let shift = &shift.clone().with_rounding(job);
         
-        // Calculate precise seconds worked
-        let basic_rate_per_second = job.get_basic_hours_base_rate_per_second();
-        
-
         match shift.shift_type {
             ShiftType::Scheduled | ShiftType::ExtraShift => {
-                
+                payments.extend(Self::get_worked_payments(shift, job, multipliers, holidays));
+
+                if let Some(allowance) = job.split_shift_allowance
+                    && shift.get_longest_gap() > allowance.min_gap
+                {
+                    payments.push(ShiftPayment {
+                        shift_id: Some(shift.id),
+                        job_id: shift.job_id,
+                        amount: allowance.amount,
+                        payment_type: ShiftPaymentType::SplitShiftAllowance,
+                        deductions: None,
+                        seconds: 0,
+                    });
+                }
             },
             ShiftType::Sick => {
                 let base_amount = 0;
                 payments.push(ShiftPayment {
-                    shift_id: shift.id,
+                    shift_id: Some(shift.id),
                     job_id: shift.job_id,
                     amount: base_amount,
                     payment_type: ShiftPaymentType::Sick,
                     deductions: None,
+                    seconds: 0,
                 });
             },
             ShiftType::Holiday | ShiftType::PaidLeave => {
                 let base_amount = 0;
                 payments.push(ShiftPayment {
-                    shift_id: shift.id,
+                    shift_id: Some(shift.id),
                     job_id: shift.job_id,
                     amount: base_amount,
                     payment_type: ShiftPaymentType::Basic,
                     deductions: None,
+                    seconds: 0,
                 });
             },
+            ShiftType::UnpaidAbsence => {},
         }
-     todo!("Add Custom Payment Type! Load from the database and add for the shift.");   
+
+        let basic_amount: u32 = payments
+            .iter()
+            .filter(|payment| payment.payment_type.is_basic())
+            .map(|payment| payment.amount)
+            .sum();
+
+        // Payments by date are paid once a day, on the day's first shift.
+        let is_first_on_date = shift.is_first_on_date(db)?;
+        let custom_payments = CustomShiftPaymentType::get_reoccuring_payments_for_period(db, job.id, shift.date, shift.date)?
+            .into_iter()
+            .filter(|custom| custom.applies_to(shift) && (custom.is_for_shift() || is_first_on_date))
+            .map(|custom| ShiftPayment {
+                shift_id: Some(shift.id),
+                job_id: shift.job_id,
+                amount: custom.get_amount(basic_amount),
+                payment_type: ShiftPaymentType::Custom(custom),
+                deductions: None,
+                seconds: 0,
+            });
+        payments.extend(custom_payments);
+
+        Ok(payments)
+    }
+
+    fn get_total(payments: &[ShiftPayment]) -> u32 {
+        payments.iter().map(|payment| payment.amount).sum()
+    }
+
+    // Expected pay for a rota day that hasn't been worked yet.
+    fn forecast_for(
+        scheduled: &ScheduledShift,
+        job: &Job,
+        db: &Database,
+        multipliers: Vec<SalaryMultiplier>,
+        holidays: &BankHolidayChecker,
+    ) -> Result<Vec<ShiftPayment>, Error> {
+        match scheduled.to_forecast_shift() {
+            Some(shift) => Self::new_for_shift(&shift, job, db, multipliers, holidays),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn mark_as_overtime(&mut self) {
+        self.payment_type = self.payment_type.to_overtime();
+    }
+    // Leaves the part of a basic line above the job's hourly rate, e.g. the 0.5 of a Sunday at x1.5.
+    fn remove_basic_pay(&mut self, job: &Job, paid_seconds: i64) {
+        if !self.payment_type.is_basic() { return }
+
+        let basic_amount = (paid_seconds as f32 * job.get_basic_hours_base_rate_per_second()).round() as u32;
+        self.amount = self.amount.saturating_sub(basic_amount);
+    }
+
+    // Moves the share of basic pay above the daily base hours into overtime lines.
+    fn split_daily_overtime(payments: &mut [ShiftPayment], paid_seconds: i64, base_seconds: i64) -> Vec<ShiftPayment> {
+        let overtime_seconds = paid_seconds - base_seconds;
+        if overtime_seconds <= 0 { return Vec::new() }
+
+        payments
+            .iter_mut()
+            .filter(|payment| payment.payment_type.is_basic())
+            .map(|payment| {
+                let moved = (payment.amount as i64 * overtime_seconds / paid_seconds) as u32;
+                let moved_seconds = payment.seconds * overtime_seconds / paid_seconds;
+                payment.amount -= moved;
+                payment.seconds -= moved_seconds;
+
+                let mut overtime = payment.clone();
+                overtime.amount = moved;
+                overtime.seconds = moved_seconds;
+                overtime.payment_type = overtime.payment_type.to_overtime();
+                overtime
+            })
+            .collect()
+    }
+
+    // Basic pay for the paid seconds (net of unpaid breaks) with the whole-shift
+    // multipliers applied, plus separate lines for the time-window and premium period uplifts.
+    fn get_worked_payments(shift: &Shift, job: &Job, multipliers: Vec<SalaryMultiplier>, holidays: &BankHolidayChecker) -> Vec<ShiftPayment> {
+        let basic_rate_per_second = job.get_basic_hours_base_rate_per_second();
+        let paid_seconds = shift.get_paid_time(job).num_seconds();
+
+        let applicable: Vec<SalaryMultiplier> = multipliers
+            .into_iter()
+            .filter(|m| m.applies_to(shift))
+            .collect();
+        let result = SalaryMultiplier::get_modifiers(shift, job, applicable);
+
+        // Multipliers without a time window apply to every paid second.
+        let whole_shift_multiplier: f32 = result.multipliers
+            .iter()
+            .chain(result.top_multiplier.iter())
+            .filter(|m| !m.is_time_window())
+            .map(|m| m.multiplier.to_floating_point())
+            .product();
+        let rate_per_second = basic_rate_per_second * whole_shift_multiplier;
+
+        let mut summaries = result.time_window_summary.unwrap_or_default();
+        // The top multiplier can be a time window too (e.g. a High priority night rate).
+        if let Some(top) = result.top_multiplier.filter(|m| m.is_time_window()) {
+            summaries.extend(TimeWindow::get_time_window_seconds_for(HashMap::from([(top, shift)]), job));
+        }
+        let (period_summaries, window_summaries): (Vec<TimeWindowSummary>, Vec<TimeWindowSummary>) = summaries
+            .into_iter()
+            .partition(|summary| summary.multiplier.premium_period.is_some());
+        let premium_seconds: f32 = window_summaries
+            .iter()
+            .map(|summary| summary.calculate_premium_amount())
+            .sum();
+
+        let (basic_type, unsociable_type) = ShiftPaymentType::for_date(shift.date, holidays);
+        let mut payments = vec![ShiftPayment {
+            shift_id: Some(shift.id),
+            job_id: shift.job_id,
+            amount: (paid_seconds as f32 * rate_per_second).round() as u32,
+            payment_type: basic_type,
+            deductions: None,
+            seconds: paid_seconds,
+        }];
+
+        if premium_seconds > 0.0 {
+            payments.push(ShiftPayment {
+                shift_id: Some(shift.id),
+                job_id: shift.job_id,
+                amount: (premium_seconds * rate_per_second).round() as u32,
+                payment_type: unsociable_type,
+                deductions: None,
+                seconds: window_summaries.iter().map(|summary| summary.seconds_worked).sum(),
+            });
+        }
+        // One line per premium period, named after its multiplier.
+        for summary in period_summaries {
+            let period_premium_seconds = summary.calculate_premium_amount();
+            if period_premium_seconds <= 0.0 { continue }
+
+            payments.push(ShiftPayment {
+                shift_id: Some(shift.id),
+                job_id: shift.job_id,
+                amount: (period_premium_seconds * rate_per_second).round() as u32,
+                payment_type: ShiftPaymentType::PremiumPeriod(summary.multiplier.name.clone()),
+                deductions: None,
+                seconds: summary.seconds_worked,
+            });
+        }
+
         payments
     }
 
 }
 
 struct PaymentSummary {
-    job_id: i32,
     period_start: NaiveDate,
     period_end: NaiveDate,
     shift_payments: Vec<ShiftPayment>,
     overtime_payments: Vec<ShiftPayment>,
-    total_deductions: Vec<ShiftPayment>,
-    total_extra: Vec<ShiftPayment>
+    total_deductions: Vec<Deduction>,
+    total_extra: Vec<ShiftPayment>,
+    // Tips, tronc and service charge, kept apart as their NI treatment differs.
+    tronc_payments: Vec<ShiftPayment>,
 }
 impl PaymentSummary {
-    fn new(from: NaiveDate, to: NaiveDate, job_id: i32) -> PaymentSummary {
-        let shift_payments: Vec<ShiftPayment> = todo!();
-        let overtime_payments: Vec<ShiftPayment> = todo!();
-        let total_deductions: Vec<ShiftPayment> = todo!();
-        let total_extra: Vec<ShiftPayment> = todo!();
-        PaymentSummary { 
-            job_id: job_id,
+    fn new(
+        from: NaiveDate,
+        to: NaiveDate,
+        job: &Job,
+        db: &Database,
+        multipliers: &HashMap<i32, Vec<SalaryMultiplier>>,
+        saved_holidays: &[BankHoliday],
+    ) -> Result<PaymentSummary, Error> {
+        let job_multipliers = SalaryMultiplier::get_for(job, multipliers).unwrap_or_default();
+        let holidays = BankHolidayChecker::for_job_between(job, from, to, saved_holidays);
+
+        let mut shift_payments: Vec<ShiftPayment> = Vec::new();
+        let mut overtime_payments: Vec<ShiftPayment> = Vec::new();
+        let mut total_extra: Vec<ShiftPayment> = Vec::new();
+        let mut tronc_payments: Vec<ShiftPayment> = Vec::new();
+
+        // Drafts generated from the rota haven't been worked yet.
+        let mut shifts = job.get_shifts_for_period_of(from, to, db)?;
+        shifts.retain(|shift| shift.is_confirmed);
+
+        if let Some(salary) = job.salary {
+            let absences: HashSet<NaiveDate> = shifts
+                .iter()
+                .filter(|shift| shift.shift_type == ShiftType::UnpaidAbsence)
+                .map(|shift| shift.date)
+                .collect();
+
+            shift_payments.push(ShiftPayment {
+                shift_id: None, // Not tied to a shift
+                job_id: job.id,
+                amount: salary.get_pay_for(job, from, to, &absences, &ScheduleOverride::get_for_job(db, job.id)?),
+                payment_type: ShiftPaymentType::Salary,
+                deductions: None,
+                seconds: 0,
+            });
+        }
+
+        for shift in shifts {
+            let mut payments = ShiftPayment::new_for_shift(&shift, job, db, job_multipliers.clone(), &holidays)?;
+            let paid_seconds = shift.clone().with_rounding(job).get_paid_time(job).num_seconds();
+
+            if shift.shift_type == ShiftType::ExtraShift {
+                payments.iter_mut().for_each(ShiftPayment::mark_as_overtime);
+            } else if job.salary.is_some() {
+                // Salary already covers basic pay for rostered hours, only the premium on top is paid.
+                payments.iter_mut().for_each(|payment| payment.remove_basic_pay(job, paid_seconds));
+                payments.retain(|payment| !payment.payment_type.is_basic() || payment.amount > 0);
+            } else if let Some(base_hours) = job.base_pay_period_hours {
+                let overtime = ShiftPayment::split_daily_overtime(&mut payments, paid_seconds, base_hours as i64 * 3600);
+                payments.extend(overtime);
+            }
+
+            for payment in payments {
+                match &payment.payment_type {
+                    ShiftPaymentType::Custom(custom) if custom.is_tronc() => tronc_payments.push(payment),
+                    ShiftPaymentType::Custom(_) => total_extra.push(payment),
+                    payment_type if payment_type.is_overtime() => overtime_payments.push(payment),
+                    _ => shift_payments.push(payment),
+                }
+            }
+        }
+
+        let total_deductions = Deduction::get_deductions_for_period(db, job.id, from, to)?;
+
+        Ok(PaymentSummary {
             period_start: from,
             period_end: to,
-            shift_payments: shift_payments,
-            overtime_payments: overtime_payments,
-            total_deductions: total_deductions,
-            total_extra: total_extra,
-          }
+            shift_payments,
+            overtime_payments,
+            total_deductions,
+            total_extra,
+            tronc_payments,
+        })
+    }
+
+    fn get_tronc_total(&self) -> u32 {
+        self.tronc_payments.iter().map(|payment| payment.amount).sum()
+    }
+    // Pay that is both taxable and NIable, tronc is never included.
+    fn get_earnings(&self) -> u32 {
+        let extras: u32 = self.total_extra
+            .iter()
+            .filter(|payment| matches!(&payment.payment_type, ShiftPaymentType::Custom(custom) if custom.is_pre_tax))
+            .map(|payment| payment.amount)
+            .sum();
+
+        self.shift_payments.iter().chain(self.overtime_payments.iter())
+            .map(|payment| payment.amount)
+            .sum::<u32>() + extras
+    }
+    fn get_pre_tax_deductions(&self) -> u32 {
+        self.total_deductions
+            .iter()
+            .filter(|deduction| deduction.is_pre_tax)
+            .map(|deduction| deduction.amount * deduction.get_occurrences(self.period_start, self.period_end))
+            .sum()
+    }
+    fn get_taxable_pay(&self) -> u32 {
+        let taxable_tronc: u32 = self.tronc_payments
+            .iter()
+            .filter(|payment| matches!(&payment.payment_type, ShiftPaymentType::Custom(custom) if custom.is_taxable != Some(false)))
+            .map(|payment| payment.amount)
+            .sum();
+
+        (self.get_earnings() + taxable_tronc).saturating_sub(self.get_pre_tax_deductions())
+    }
+    fn get_niable_pay(&self) -> u32 {
+        let niable_tronc: u32 = self.tronc_payments
+            .iter()
+            .filter(|payment| matches!(&payment.payment_type, ShiftPaymentType::Custom(custom) if !custom.is_ni_exempt))
+            .map(|payment| payment.amount)
+            .sum();
+
+        self.get_earnings() + niable_tronc
     }
     // fn get_gross(&self) -> u32 {
     //     self.payments.iter().map(|payment| {
@@ -1145,6 +5248,441 @@ impl PaymentSummary {
     //     }).sum()
     // }
 }
+// PAYSLIPS
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum PayslipPeriod {
+    Weekly, // By TaxWeek
+    Monthly, // Calendar months, April is tax month 1
+}
+// Every ShiftPayment of one type added together, as one row of the payments column.
+#[derive(PartialEq, Debug, Clone)]
+struct PayslipLine {
+    name: String,
+    seconds: i64, // 0 for fixed amounts, which have no hours or rate
+    amount: u32,
+}
+impl PayslipLine {
+    fn group(payments: &[&ShiftPayment]) -> Vec<PayslipLine> {
+        let mut lines: Vec<PayslipLine> = Vec::new();
+        for payment in payments {
+            let name = payment.payment_type.get_name();
+            match lines.iter_mut().find(|line| line.name == name) {
+                Some(line) => {
+                    line.seconds += payment.seconds;
+                    line.amount += payment.amount;
+                },
+                None => lines.push(PayslipLine { name, seconds: payment.seconds, amount: payment.amount }),
+            }
+        }
+        lines
+    }
+    fn get_hours(&self) -> Option<f32> {
+        (self.seconds > 0).then(|| self.seconds as f32 / 3600.0)
+    }
+    // Pence per hour.
+    fn get_rate(&self) -> Option<u32> {
+        self.get_hours().map(|hours| (self.amount as f32 / hours).round() as u32)
+    }
+}
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+struct PayslipTotals {
+    gross: u32,
+    taxable: u32,
+    niable: u32,
+    tax: u32,
+    national_insurance: u32,
+    student_loan: u32,
+    pension: u32,
+    net: u32,
+}
+impl PayslipTotals {
+    fn add(&mut self, other: PayslipTotals) {
+        self.gross += other.gross;
+        self.taxable += other.taxable;
+        self.niable += other.niable;
+        self.tax += other.tax;
+        self.national_insurance += other.national_insurance;
+        self.student_loan += other.student_loan;
+        self.pension += other.pension;
+        self.net += other.net;
+    }
+}
+// Tax is worked out on a week 1/month 1 basis, each period on its own, so it can differ
+// from a payslip taxed cumulatively when pay goes up and down through the year.
+#[derive(PartialEq, Debug, Clone)]
+struct Payslip {
+    period: PayslipPeriod,
+    period_number: u32, // Tax week or tax month
+    financial_year: String,
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    lines: Vec<PayslipLine>,
+    extras: Vec<PayslipLine>, // Custom payments, tips and tronc
+    post_tax_additions: Vec<PayslipLine>,
+    pre_tax_deductions: Vec<(String, u32)>,
+    post_tax_deductions: Vec<(String, u32)>,
+    this_period: PayslipTotals,
+    year_to_date: PayslipTotals,
+}
+impl Payslip {
+    fn new(
+        db: &Database,
+        job: &Job,
+        multipliers: &HashMap<i32, Vec<SalaryMultiplier>>,
+        saved_holidays: &[BankHoliday],
+        period: PayslipPeriod,
+        date: NaiveDate,
+    ) -> Result<Payslip, Error> {
+        let (period_start, period_end) = Self::get_period_containing(date, period, job);
+        let summary = PaymentSummary::new(period_start, period_end, job, db, multipliers, saved_holidays)?;
+        let this_period = Self::get_totals(&summary, job, period);
+
+        // Every earlier period of the tax year, added up.
+        let financial_year = TaxWeek::get_financial_year(period_end);
+        let mut year_to_date = this_period;
+        let mut earlier = period_start.pred_opt().unwrap();
+        loop {
+            let (start, end) = Self::get_period_containing(earlier, period, job);
+            if TaxWeek::get_financial_year(end) != financial_year { break }
+
+            year_to_date.add(Self::get_totals(&PaymentSummary::new(start, end, job, db, multipliers, saved_holidays)?, job, period));
+            earlier = start.pred_opt().unwrap();
+        }
+
+        let is_pre_tax = |payment: &&ShiftPayment| matches!(&payment.payment_type, ShiftPaymentType::Custom(custom) if custom.is_pre_tax);
+        let period_deductions = |is_pre_tax: bool| -> Vec<(String, u32)> {
+            summary.total_deductions
+                .iter()
+                .filter(|deduction| deduction.is_pre_tax == is_pre_tax)
+                .map(|deduction| (deduction.name.clone(), deduction.amount * deduction.get_occurrences(period_start, period_end)))
+                .filter(|(_, amount)| *amount > 0)
+                .collect()
+        };
+
+        Ok(Payslip {
+            period,
+            period_number: match period {
+                PayslipPeriod::Weekly => TaxWeek::new(period_start, job.get_tax_week_start()).week_commencing as u32,
+                PayslipPeriod::Monthly => (period_start.month() + 8) % 12 + 1,
+            },
+            financial_year,
+            period_start,
+            period_end,
+            lines: PayslipLine::group(&summary.shift_payments.iter().chain(summary.overtime_payments.iter()).collect::<Vec<_>>()),
+            extras: PayslipLine::group(&summary.total_extra.iter().filter(is_pre_tax).chain(summary.tronc_payments.iter()).collect::<Vec<_>>()),
+            post_tax_additions: PayslipLine::group(&summary.total_extra.iter().filter(|payment| !is_pre_tax(payment)).collect::<Vec<_>>()),
+            pre_tax_deductions: period_deductions(true),
+            post_tax_deductions: period_deductions(false),
+            this_period,
+            year_to_date,
+        })
+    }
+
+    fn get_period_containing(date: NaiveDate, period: PayslipPeriod, job: &Job) -> (NaiveDate, NaiveDate) {
+        match period {
+            PayslipPeriod::Weekly => {
+                let start = TaxWeek::new(date, job.get_tax_week_start()).week_start_date;
+                (start, start + TimeDelta::days(6))
+            },
+            // Tax months run from the 6th to the 5th, tax month 1 starts on 6 April.
+            PayslipPeriod::Monthly => {
+                let month_start = date.with_day(1).unwrap();
+                let start = if date.day() >= 6 { month_start } else { month_start.checked_sub_months(chrono::Months::new(1)).unwrap() }
+                    .with_day(6)
+                    .unwrap();
+                (start, start.checked_add_months(chrono::Months::new(1)).unwrap().pred_opt().unwrap())
+            },
+        }
+    }
+
+    fn get_periods_in_year(period: PayslipPeriod) -> u32 {
+        match period {
+            PayslipPeriod::Weekly => 52,
+            PayslipPeriod::Monthly => 12,
+        }
+    }
+
+    fn get_totals(summary: &PaymentSummary, job: &Job, period: PayslipPeriod) -> PayslipTotals {
+        let periods = Self::get_periods_in_year(period);
+        let post_tax_additions: u32 = summary.total_extra
+            .iter()
+            .filter(|payment| matches!(&payment.payment_type, ShiftPaymentType::Custom(custom) if !custom.is_pre_tax))
+            .map(|payment| payment.amount)
+            .sum();
+        let post_tax_deductions: u32 = summary.total_deductions
+            .iter()
+            .filter(|deduction| !deduction.is_pre_tax)
+            .map(|deduction| deduction.amount * deduction.get_occurrences(summary.period_start, summary.period_end))
+            .sum();
+
+        let gross = summary.get_earnings() + summary.get_tronc_total();
+        let pension = job.pension.map(|pension| pension.get_contribution(summary.get_earnings(), periods)).unwrap_or(0);
+        let method = job.pension.map(|pension| pension.method);
+
+        let taxable = match method {
+            Some(PensionMethod::NetPay | PensionMethod::SalarySacrifice) => summary.get_taxable_pay().saturating_sub(pension),
+            _ => summary.get_taxable_pay(),
+        };
+        let niable = match method {
+            Some(PensionMethod::SalarySacrifice) => summary.get_niable_pay().saturating_sub(pension),
+            _ => summary.get_niable_pay(),
+        };
+        // Relief at source pension providers claim the basic rate tax back themselves.
+        let pension_deducted = match method {
+            Some(PensionMethod::ReliefAtSource) => pension * 80 / 100,
+            _ => pension,
+        };
+
+        // Worked out on the pay as if it were earned all year, then shared back out per period.
+        let annualised = |amount: u32, calculate: fn(&TaxSummary) -> u32| {
+            calculate(&TaxSummary::for_annual_amount(amount * periods, job.get_region())) / periods
+        };
+        let tax = annualised(taxable, |summary| summary.get_tax_prediction());
+        let national_insurance = annualised(niable, |summary| summary.get_national_insurance_prediction());
+        let student_loan = job.student_loans.iter().map(|plan| plan.get_repayment(niable, periods)).sum();
+
+        let net = (gross + post_tax_additions)
+            .saturating_sub(summary.get_pre_tax_deductions())
+            .saturating_sub(tax + national_insurance + student_loan + pension_deducted + post_tax_deductions);
+
+        PayslipTotals { gross, taxable, niable, tax, national_insurance, student_loan, pension: pension_deducted, net }
+    }
+}
+
+// DASHBOARD
+
+// Everything earned in one week or month of the tax year, over every job shown.
+#[derive(PartialEq, Debug, Clone)]
+struct EarningsPeriod {
+    label: String, // e.g. "Apr" or "W1"
+    totals: PayslipTotals,
+    seconds: i64, // Paid time, premiums aren't counted twice
+    hourly_pay: u32, // Pay for that time with its premiums, without salary, leave or tronc
+    premium_seconds: Vec<(String, i64)>,
+}
+impl EarningsPeriod {
+    // Pence per hour, pay for the hours over the hours paid.
+    fn get_average_hourly_rate(&self) -> Option<u32> {
+        (self.seconds > 0).then(|| (self.hourly_pay as f64 / (self.seconds as f64 / 3600.0)).round() as u32)
+    }
+}
+#[derive(PartialEq, Debug, Clone)]
+struct EarningsDashboard {
+    periods: Vec<EarningsPeriod>,
+    // Last tax year up to the same date, e.g. 6 April to 18 October both years.
+    last_year: Vec<EarningsPeriod>,
+}
+impl EarningsDashboard {
+    fn new(
+        db: &Database,
+        jobs: &[&Job],
+        multipliers: &HashMap<i32, Vec<SalaryMultiplier>>,
+        saved_holidays: &[BankHoliday],
+        period: PayslipPeriod,
+        tax_year: i32, // The year it starts in, e.g. 2026 for 2026/2027
+        today: NaiveDate,
+    ) -> Result<EarningsDashboard, Error> {
+        let year_start = NaiveDate::from_ymd_opt(tax_year, 4, 6).unwrap();
+        let year_end = NaiveDate::from_ymd_opt(tax_year + 1, 4, 5).unwrap();
+        let last_year_start = NaiveDate::from_ymd_opt(tax_year - 1, 4, 6).unwrap();
+        let last_year_end = year_end.min(today).checked_sub_months(chrono::Months::new(12)).unwrap();
+
+        Ok(EarningsDashboard {
+            periods: Self::get_periods(db, jobs, multipliers, saved_holidays, period, year_start, year_end.min(today))?,
+            last_year: Self::get_periods(db, jobs, multipliers, saved_holidays, period, last_year_start, last_year_end)?,
+        })
+    }
+
+    // Periods are counted from the start of the tax year, so jobs with different
+    // tax week starts still line up week for week.
+    fn get_periods(
+        db: &Database,
+        jobs: &[&Job],
+        multipliers: &HashMap<i32, Vec<SalaryMultiplier>>,
+        saved_holidays: &[BankHoliday],
+        period: PayslipPeriod,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<EarningsPeriod>, Error> {
+        let mut periods: Vec<EarningsPeriod> = Vec::new();
+
+        for job in jobs {
+            let mut date = from;
+            let mut index = 0;
+            while date <= to {
+                let (period_start, period_end) = Payslip::get_period_containing(date, period, job);
+                // Only the part inside [from, to], e.g. a week 1 that starts before 6 April.
+                let (start, end) = (period_start.max(from), period_end.min(to));
+                let summary = PaymentSummary::new(start, end, job, db, multipliers, saved_holidays)?;
+
+                if periods.len() <= index {
+                    periods.push(EarningsPeriod {
+                        label: match period {
+                            PayslipPeriod::Weekly => format!("W{}", index + 1),
+                            PayslipPeriod::Monthly => period_start.format("%b").to_string(),
+                        },
+                        totals: PayslipTotals::default(),
+                        seconds: 0,
+                        hourly_pay: 0,
+                        premium_seconds: Vec::new(),
+                    });
+                }
+                let earnings = &mut periods[index];
+                earnings.totals.add(Payslip::get_totals(&summary, job, period));
+
+                for payment in summary.shift_payments.iter().chain(summary.overtime_payments.iter()) {
+                    if payment.seconds > 0 { earnings.hourly_pay += payment.amount }
+                    if payment.payment_type.is_basic() || (payment.payment_type.is_overtime() && payment.payment_type != ShiftPaymentType::UnsociableOvertime) {
+                        earnings.seconds += payment.seconds;
+                    } else if payment.seconds > 0 {
+                        let name = payment.payment_type.get_name();
+                        match earnings.premium_seconds.iter_mut().find(|(premium, _)| *premium == name) {
+                            Some((_, seconds)) => *seconds += payment.seconds,
+                            None => earnings.premium_seconds.push((name, payment.seconds)),
+                        }
+                    }
+                }
+
+                date = period_end.succ_opt().unwrap();
+                index += 1;
+            }
+        }
+
+        Ok(periods)
+    }
+
+    fn get_totals(&self) -> PayslipTotals {
+        Self::add_up(&self.periods)
+    }
+    fn get_last_year_totals(&self) -> PayslipTotals {
+        Self::add_up(&self.last_year)
+    }
+    fn add_up(periods: &[EarningsPeriod]) -> PayslipTotals {
+        let mut totals = PayslipTotals::default();
+        periods.iter().for_each(|earnings| totals.add(earnings.totals));
+        totals
+    }
+
+    fn get_seconds(&self) -> i64 {
+        self.periods.iter().map(|earnings| earnings.seconds).sum()
+    }
+    fn get_average_hourly_rate(&self) -> Option<u32> {
+        let seconds = self.get_seconds();
+        let hourly_pay: u32 = self.periods.iter().map(|earnings| earnings.hourly_pay).sum();
+        (seconds > 0).then(|| (hourly_pay as f64 / (seconds as f64 / 3600.0)).round() as u32)
+    }
+    // Premium time over the year, most hours first.
+    fn get_premium_seconds(&self) -> Vec<(String, i64)> {
+        let mut premium: Vec<(String, i64)> = Vec::new();
+        for (name, seconds) in self.periods.iter().flat_map(|earnings| earnings.premium_seconds.iter()) {
+            match premium.iter_mut().find(|(existing, _)| existing == name) {
+                Some((_, total)) => *total += seconds,
+                None => premium.push((name.clone(), *seconds)),
+            }
+        }
+        premium.sort_by_key(|(_, seconds)| std::cmp::Reverse(*seconds));
+        premium
+    }
+}
+
+// How many minutes clock rounding has gained (positive) or cost (negative) over a period.
+struct RoundingReport {
+    shifts_rounded: usize,
+    minutes_gained: i64,
+}
+impl RoundingReport {
+    fn new(db: &Database, job: &Job, from: NaiveDate, to: NaiveDate) -> Result<RoundingReport, Error> {
+        let shifts = job.get_shifts_for_period_of(from, to, db)?;
+
+        let mut shifts_rounded = 0;
+        let mut seconds_gained = 0;
+
+        for shift in shifts.into_iter().filter(|shift| shift.is_confirmed && shift.shift_type.is_worked()) {
+            let rounded = shift.with_rounding(job);
+            let difference = (rounded.get_time_worked() - rounded.get_raw_time_worked()).num_seconds();
+
+            if difference != 0 { shifts_rounded += 1 }
+            seconds_gained += difference;
+        }
+
+        Ok(RoundingReport {
+            shifts_rounded,
+            minutes_gained: seconds_gained / 60,
+        })
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum LieuReason {
+    WorkedBankHoliday,
+    BankHolidayOnDayOff,
+}
+#[derive(PartialEq, Debug, Clone)]
+struct LieuAccrual {
+    date: NaiveDate,
+    holiday: String,
+    reason: LieuReason,
+}
+// Days in lieu earned from bank holidays against PaidLeave shifts taken as lieu days.
+struct LieuBalance {
+    accrued: Vec<LieuAccrual>,
+    taken: Vec<NaiveDate>,
+}
+impl LieuBalance {
+    // Only confirmed shifts up to today count, rota drafts for days still to come haven't happened yet.
+    fn new(
+        db: &Database,
+        job: &Job,
+        from: NaiveDate,
+        to: NaiveDate,
+        today: NaiveDate,
+        overrides: &[ScheduleOverride],
+    ) -> Result<LieuBalance, Error> {
+        let mut shifts = job.get_shifts_for_period_of(from, to, db)?;
+        shifts.retain(|shift| shift.is_confirmed && shift.date <= today);
+        let taken = shifts
+            .iter()
+            .filter(|shift| shift.shift_type == ShiftType::PaidLeave && shift.is_lieu_day)
+            .map(|shift| shift.date)
+            .collect();
+
+        let Some(policy) = job.lieu_policy else {
+            return Ok(LieuBalance { accrued: Vec::new(), taken })
+        };
+
+        let saved = BankHoliday::load_all(db)?;
+        let mut accrued = Vec::new();
+
+        for year in from.year()..=to.year() {
+            let holidays = BankHolidayChecker::for_job(job, year, &saved);
+
+            for holiday in holidays.holidays.iter().filter(|holiday| holiday.date >= from && holiday.date <= to.min(today)) {
+                let worked = shifts.iter().any(|shift| {
+                    shift.date == holiday.date && matches!(shift.shift_type, ShiftType::Scheduled | ShiftType::ExtraShift)
+                });
+                let is_day_off = job
+                    .get_scheduled_shift_on(holiday.date, overrides)
+                    .is_some_and(|scheduled| scheduled.status == ShiftStatus::OFF);
+
+                let reason = if worked && policy.for_worked_bank_holiday {
+                    LieuReason::WorkedBankHoliday
+                } else if !worked && is_day_off && policy.for_bank_holiday_on_day_off {
+                    LieuReason::BankHolidayOnDayOff
+                } else {
+                    continue
+                };
+                accrued.push(LieuAccrual { date: holiday.date, holiday: holiday.name.clone(), reason });
+            }
+        }
+
+        Ok(LieuBalance { accrued, taken })
+    }
+    fn get_balance(&self) -> i32 {
+        self.accrued.len() as i32 - self.taken.len() as i32
+    }
+}
+
 #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
 enum UKRegion {
     England,
@@ -1153,15 +5691,16 @@ enum UKRegion {
     Scotland,
 }
 
-struct TaxSummary<'a> {
-    shift_payment: &'a ShiftPayment,
+struct TaxSummary {
+    annual_gross: u32,
     region: UKRegion,
 }
 
-impl<'a> TaxSummary<'a> {
-    fn new(payment: &'a ShiftPayment, region: UKRegion) -> TaxSummary<'a> {
+impl TaxSummary {
+    // For pay that isn't a single payment, e.g. a payslip period's pay as if earned all year.
+    fn for_annual_amount(annual_gross: u32, region: UKRegion) -> TaxSummary {
         TaxSummary {
-            shift_payment: payment,
+            annual_gross,
             region
         }
     }
@@ -1169,7 +5708,7 @@ impl<'a> TaxSummary<'a> {
     // Calculate income tax based on annual gross income
     // Amounts are in pence (u32), so £12,570 = 1,257,000 pence
     fn get_tax_prediction(&self) -> u32 {
-        let annual_gross = self.shift_payment.amount;
+        let annual_gross = self.annual_gross;
 
         match self.region {
             UKRegion::Scotland => self.calculate_scottish_income_tax(annual_gross),
@@ -1290,7 +5829,7 @@ impl<'a> TaxSummary<'a> {
     // Calculate National Insurance contributions (same across all UK regions)
     // Class 1 Employee rates 2026/27
     fn get_national_insurance_prediction(&self) -> u32 {
-        let annual_gross_pence = self.shift_payment.amount;
+        let annual_gross_pence = self.annual_gross;
 
         // Annual thresholds in pence
         let primary_threshold = 1_257_000; // £12,570 (£242/week * 52)
@@ -1316,26 +5855,6 @@ impl<'a> TaxSummary<'a> {
 
         ni
     }
-
-    fn get_gross_after_deductions(&self) -> u32 {
-        let gross = self.shift_payment.amount;
-        let total_deductions = self.get_total_deductions();
-        gross.saturating_sub(total_deductions)
-    }
-
-    fn get_total_deductions(&self) -> u32 {
-        let tax = self.get_tax_prediction();
-        let ni = self.get_national_insurance_prediction();
-
-        // Add any custom deductions if present
-        let custom_deductions = if let Some(ref deductions) = self.shift_payment.deductions {
-            deductions.iter().map(|d| d.amount).sum()
-        } else {
-            0
-        };
-
-        tax + ni + custom_deductions
-    }
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
@@ -1347,15 +5866,85 @@ enum ShiftPaymentType {
     Saturday,
     UnsociableSaturday,
     Overtime,
+    SaturdayOvertime,
+    SundayOvertime,
+    BankHolidayOvertime,
     UnsociableOvertime,
     BankHoliday,
     UnsociableBankHoliday,
-    Christmass,
+    PremiumPeriod(String), // The multiplier's name, e.g. "Christmas Day"
     Sick,
+    Salary,
+    SplitShiftAllowance,
 
     // For example a bonus
     Custom(CustomShiftPaymentType),
 }
+impl ShiftPaymentType {
+    // As printed on a payslip.
+    fn get_name(&self) -> String {
+        match self {
+            ShiftPaymentType::Basic => "Basic pay",
+            ShiftPaymentType::UnsociableBasic => "Unsociable hours",
+            ShiftPaymentType::Sunday => "Sunday",
+            ShiftPaymentType::UnsociableSunday => "Sunday unsociable hours",
+            ShiftPaymentType::Saturday => "Saturday",
+            ShiftPaymentType::UnsociableSaturday => "Saturday unsociable hours",
+            ShiftPaymentType::Overtime => "Overtime",
+            ShiftPaymentType::SaturdayOvertime => "Saturday overtime",
+            ShiftPaymentType::SundayOvertime => "Sunday overtime",
+            ShiftPaymentType::BankHolidayOvertime => "Bank holiday overtime",
+            ShiftPaymentType::UnsociableOvertime => "Overtime unsociable hours",
+            ShiftPaymentType::BankHoliday => "Bank holiday",
+            ShiftPaymentType::UnsociableBankHoliday => "Bank holiday unsociable hours",
+            ShiftPaymentType::PremiumPeriod(name) => return name.clone(),
+            ShiftPaymentType::Sick => "Sick pay",
+            ShiftPaymentType::Salary => "Salary",
+            ShiftPaymentType::SplitShiftAllowance => "Split shift allowance",
+            ShiftPaymentType::Custom(custom) => return custom.name.clone(),
+        }.to_string()
+    }
+    // Pay for the hours themselves, before any premiums or extras.
+    fn is_basic(&self) -> bool {
+        matches!(self, ShiftPaymentType::Basic | ShiftPaymentType::Saturday | ShiftPaymentType::Sunday | ShiftPaymentType::BankHoliday)
+    }
+    fn is_overtime(&self) -> bool {
+        matches!(
+            self,
+            ShiftPaymentType::Overtime
+                | ShiftPaymentType::SaturdayOvertime
+                | ShiftPaymentType::SundayOvertime
+                | ShiftPaymentType::BankHolidayOvertime
+                | ShiftPaymentType::UnsociableOvertime
+        )
+    }
+    // The overtime line for the same day, so a Sunday stays a Sunday once it's overtime.
+    fn to_overtime(&self) -> ShiftPaymentType {
+        match self {
+            ShiftPaymentType::Basic => ShiftPaymentType::Overtime,
+            ShiftPaymentType::Saturday => ShiftPaymentType::SaturdayOvertime,
+            ShiftPaymentType::Sunday => ShiftPaymentType::SundayOvertime,
+            ShiftPaymentType::BankHoliday => ShiftPaymentType::BankHolidayOvertime,
+            ShiftPaymentType::UnsociableBasic
+            | ShiftPaymentType::UnsociableSaturday
+            | ShiftPaymentType::UnsociableSunday
+            | ShiftPaymentType::UnsociableBankHoliday => ShiftPaymentType::UnsociableOvertime,
+            ShiftPaymentType::PremiumPeriod(name) => ShiftPaymentType::PremiumPeriod(format!("{name} overtime")),
+            other => other.clone(),
+        }
+    }
+    // Basic and unsociable payment types for the day a shift is on.
+    fn for_date(date: NaiveDate, holidays: &BankHolidayChecker) -> (ShiftPaymentType, ShiftPaymentType) {
+        if holidays.is_bank_holiday(date) {
+            return (ShiftPaymentType::BankHoliday, ShiftPaymentType::UnsociableBankHoliday)
+        }
+        match date.weekday() {
+            Weekday::Sat => (ShiftPaymentType::Saturday, ShiftPaymentType::UnsociableSaturday),
+            Weekday::Sun => (ShiftPaymentType::Sunday, ShiftPaymentType::UnsociableSunday),
+            _ => (ShiftPaymentType::Basic, ShiftPaymentType::UnsociableBasic),
+        }
+    }
+}
 #[native_model(id = 2, version = 1)]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[native_db]
@@ -1376,7 +5965,16 @@ struct CustomShiftPaymentType {
     amount: Option<u32>,
     schedule: ReocurrementSchedule,
     is_pre_tax: bool, // true = increases taxable income, false = post-tax addition (e.g. bonus)
-
+    income_type: IncomeType,
+    // Tronc shared out by an independent troncmaster is still taxed, but has no NI.
+    is_ni_exempt: bool,
+}
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+enum IncomeType {
+    Standard, // Bonuses, allowances etc.
+    Tronc,
+    CardTips,
+    ServiceCharge,
 }
 /*
 
@@ -1388,6 +5986,54 @@ ShiftPaymentType is generated automatically based on the day >
 */
 
 impl CustomShiftPaymentType {
+    // Tips, tronc or service charge entered against a single shift.
+    fn new_tip(
+        id_gen: &IdGenerator,
+        shift: &Shift,
+        name: String,
+        income_type: IncomeType,
+        amount: u32,
+        is_ni_exempt: bool,
+    ) -> CustomShiftPaymentType {
+        CustomShiftPaymentType {
+            id: id_gen.next_custom_payment_id(),
+            job_id: shift.job_id,
+            shift_id: shift.id,
+            name,
+            is_taxable: Some(true),
+            day: Some(shift.date),
+            multiplier: None,
+            amount: Some(amount),
+            schedule: ReocurrementSchedule::OneTime {
+                date: shift.date,
+                date_key: Shift::date_to_key(shift.date),
+                shift_id: Some(shift.id),
+            },
+            is_pre_tax: true,
+            income_type,
+            is_ni_exempt,
+        }
+    }
+    fn is_for_shift(&self) -> bool {
+        matches!(self.schedule, ReocurrementSchedule::OneTime { shift_id: Some(_), .. })
+    }
+    fn is_tronc(&self) -> bool {
+        self.income_type != IncomeType::Standard
+    }
+    // One-off payments tied to a shift only apply to that shift,
+    // everything else applies to any shift on a day the schedule covers.
+    fn applies_to(&self, shift: &Shift) -> bool {
+        match self.schedule {
+            ReocurrementSchedule::OneTime { shift_id: Some(id), .. } => id == shift.id,
+            _ => self.schedule.applies_on(shift.date),
+        }
+    }
+    // Fixed amount plus the multiplier's share of the shift's basic pay (0.1 = 10%).
+    fn get_amount(&self, basic_amount: u32) -> u32 {
+        let fixed = self.amount.unwrap_or(0);
+        let multiplied = self.multiplier.map_or(0, |m| (basic_amount as f64 * m).round() as u32);
+        fixed + multiplied
+    }
     fn get_reoccuring_payments_for_period(
         db: &Database,
         job_id: i32,
@@ -1419,7 +6065,816 @@ impl CustomShiftPaymentType {
         
         Ok(applicable)
     }
-    
+
+}
+
+// WORKING TIME REGULATIONS
+
+// Limits from the Working Time Regulations 1998, in seconds.
+const WTR_MAX_WEEKLY_AVERAGE_SECONDS: i64 = 48 * 3600;
+const WTR_REFERENCE_PERIOD_WEEKS: i64 = 17;
+const WTR_DAILY_REST_SECONDS: i64 = 11 * 3600;
+const WTR_WEEKLY_REST_SECONDS: i64 = 24 * 3600;
+const WTR_FORTNIGHTLY_REST_SECONDS: i64 = 48 * 3600;
+const WTR_BREAK_THRESHOLD_SECONDS: i64 = 6 * 3600;
+const WTR_MIN_BREAK_SECONDS: i64 = 20 * 60;
+
+// A single stretch of work, either logged as a Shift or taken from the rota.
+#[derive(PartialEq, Debug, Clone)]
+struct WorkPeriod {
+    job_id: i32,
+    shift_id: Option<i32>, // None = scheduled on the rota, not logged yet
+    date: NaiveDate,
+    start: NaiveDateTime,
+    finish: NaiveDateTime,
+    break_seconds: i64,
+    // Gaps in a split shift aren't working time, but don't count as rest between shifts either.
+    gaps: Vec<ShiftSegment>,
+}
+
+impl WorkPeriod {
+    // Only shifts that were actually worked count as working time.
+    fn from_shift(shift: &Shift, job: Option<&Job>) -> Option<WorkPeriod> {
+        let gaps = shift.get_gaps();
+        let gap_seconds: i64 = gaps.iter().map(|gap| gap.get_duration().num_seconds()).sum();
+        let break_seconds = gap_seconds + match job {
+            Some(job) => shift.get_unpaid_break(job).num_seconds(),
+            None => shift.unpaid_break.map_or(0, |recorded| recorded.duration.num_seconds()),
+        };
+
+        match shift.shift_type {
+            ShiftType::Scheduled | ShiftType::ExtraShift => Some(WorkPeriod {
+                job_id: shift.job_id,
+                shift_id: Some(shift.id),
+                date: shift.date,
+                start: shift.start,
+                finish: shift.finish,
+                break_seconds,
+                gaps,
+            }),
+            _ => None,
+        }
+    }
+    // Rota days can only be checked when they have expected times.
+    fn from_scheduled(job: &Job, scheduled: &ScheduledShift) -> Option<WorkPeriod> {
+        if scheduled.status != ShiftStatus::ON { return None }
+
+        let start = scheduled.start?;
+        let finish = scheduled.finish?;
+
+        Some(WorkPeriod {
+            job_id: job.id,
+            shift_id: None,
+            date: scheduled.date,
+            start,
+            finish,
+            break_seconds: job.get_unpaid_break_for(finish - start).num_seconds(),
+            gaps: Vec::new(),
+        })
+    }
+    fn get_seconds(&self) -> i64 {
+        self.get_seconds_between(self.start, self.finish)
+    }
+    // Seconds of this period that fall inside [from, to), less any split shift gaps.
+    fn get_seconds_between(&self, from: NaiveDateTime, to: NaiveDateTime) -> i64 {
+        let overlap = |start: NaiveDateTime, finish: NaiveDateTime| {
+            let start = start.max(from);
+            let finish = finish.min(to);
+            if finish > start { (finish - start).num_seconds() } else { 0 }
+        };
+        let gap_seconds: i64 = self.gaps.iter().map(|gap| overlap(gap.start, gap.finish)).sum();
+
+        overlap(self.start, self.finish) - gap_seconds
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+enum WorkingTimeBreach {
+    // Average over the 17 weeks ending on week_end is above 48 hours.
+    WeeklyAverageExceeded { week_end: NaiveDate, average_seconds: i64 },
+    // Less than 11 hours between finishing one shift and starting the next (any job).
+    InsufficientDailyRest { finish: NaiveDateTime, next_start: NaiveDateTime, rest_seconds: i64 },
+    // Neither 24 hours off in each week nor 48 hours off in the fortnight.
+    InsufficientWeeklyRest { fortnight_start: NaiveDate, longest_rest_seconds: i64 },
+    // Shift over 6 hours without a 20 minute break (shift_id None = rota day).
+    MissingRestBreak { date: NaiveDate, job_id: i32, shift_id: Option<i32>, worked_seconds: i64 },
+}
+
+impl WorkingTimeBreach {
+    // The day the breach should be shown on in the calendar.
+    fn date(&self) -> NaiveDate {
+        match self {
+            WorkingTimeBreach::WeeklyAverageExceeded { week_end, .. } => *week_end,
+            WorkingTimeBreach::InsufficientDailyRest { next_start, .. } => next_start.date(),
+            WorkingTimeBreach::InsufficientWeeklyRest { fortnight_start, .. } => *fortnight_start,
+            WorkingTimeBreach::MissingRestBreak { date, .. } => *date,
+        }
+    }
+    fn describe(&self) -> String {
+        let pretty = |seconds: i64| format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60);
+
+        match self {
+            WorkingTimeBreach::WeeklyAverageExceeded { average_seconds, .. } => {
+                format!("Average week of {} over the last {} weeks (limit 48h)", pretty(*average_seconds), WTR_REFERENCE_PERIOD_WEEKS)
+            },
+            WorkingTimeBreach::InsufficientDailyRest { finish, next_start, rest_seconds } => {
+                format!("Only {} rest between {} and {} (minimum 11h)", pretty(*rest_seconds), finish, next_start)
+            },
+            WorkingTimeBreach::InsufficientWeeklyRest { longest_rest_seconds, .. } => {
+                format!("Longest rest in the fortnight was {} (24h a week or 48h a fortnight required)", pretty(*longest_rest_seconds))
+            },
+            WorkingTimeBreach::MissingRestBreak { worked_seconds, .. } => {
+                format!("Shift of {} without a 20 minute break", pretty(*worked_seconds))
+            },
+        }
+    }
+}
+
+struct WorkingTimeChecker {
+    periods: Vec<WorkPeriod>, // sorted by start, across all jobs
+}
+
+impl WorkingTimeChecker {
+    fn new(mut periods: Vec<WorkPeriod>) -> Self {
+        periods.sort_by_key(|period| period.start);
+        Self { periods }
+    }
+
+    // Loads confirmed shifts for the given jobs, filling the gaps with scheduled rota days.
+    // Goes back a full reference period so the weekly average can be worked out.
+    fn load(
+        db: &Database,
+        jobs: &[&Job],
+        from: NaiveDate,
+        to: NaiveDate
+    ) -> Result<Self, Error> {
+        let reference_start = from - TimeDelta::weeks(WTR_REFERENCE_PERIOD_WEEKS);
+        let jobs: HashMap<i32, &Job> = jobs.iter().map(|job| (job.id, *job)).collect();
+        let mut shifts = Shift::get_shifts_for_period(db, reference_start, to, None)?;
+        shifts.retain(|shift| shift.is_confirmed && jobs.contains_key(&shift.job_id));
+
+        let logged: HashSet<(i32, NaiveDate)> = shifts
+            .iter()
+            .map(|shift| (shift.job_id, shift.date))
+            .collect();
+
+        let mut periods: Vec<WorkPeriod> = shifts
+            .iter()
+            .filter_map(|shift| WorkPeriod::from_shift(shift, jobs.get(&shift.job_id).copied()))
+            .collect();
+
+        let overrides = ScheduleOverride::load_all(db)?;
+
+        for job in jobs.values() {
+            let job_overrides = overrides.get(&job.id).map(Vec::as_slice).unwrap_or_default();
+            let scheduled = job
+                .scheduled_shifts(reference_start, to, job_overrides)
+                .filter(|day| !logged.contains(&(job.id, day.date)))
+                .filter_map(|day| WorkPeriod::from_scheduled(job, &day));
+
+            periods.extend(scheduled);
+        }
+
+        Ok(Self::new(periods))
+    }
+
+    // Also checks the fortnight either side, so a week or fortnight that only
+    // partly falls inside [from, to] is still caught, e.g. at a month boundary.
+    fn check_around(db: &Database, jobs: &[&Job], from: NaiveDate, to: NaiveDate) -> Result<Vec<WorkingTimeBreach>, Error> {
+        let (from, to) = (from - TimeDelta::days(13), to + TimeDelta::days(13));
+        Ok(Self::load(db, jobs, from, to)?.check(from, to))
+    }
+
+    fn check(&self, from: NaiveDate, to: NaiveDate) -> Vec<WorkingTimeBreach> {
+        let mut breaches = Vec::new();
+
+        breaches.extend(self.check_weekly_average(from, to));
+        breaches.extend(self.check_daily_rest(from, to));
+        breaches.extend(self.check_weekly_rest(from, to));
+        breaches.extend(self.check_rest_breaks(from, to));
+
+        breaches.sort_by_key(|breach| breach.date());
+        breaches
+    }
+
+    fn breaches_on(breaches: &[WorkingTimeBreach], date: NaiveDate) -> Vec<&WorkingTimeBreach> {
+        breaches.iter().filter(|breach| breach.date() == date).collect()
+    }
+
+    // Rolling 17 week average, checked at the end of every week in the period.
+    fn check_weekly_average(&self, from: NaiveDate, to: NaiveDate) -> Vec<WorkingTimeBreach> {
+        let mut breaches = Vec::new();
+        let mut week_end = from + TimeDelta::days(6);
+
+        while week_end <= to {
+            let reference_end = week_end.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap();
+            let reference_start = reference_end - TimeDelta::weeks(WTR_REFERENCE_PERIOD_WEEKS);
+
+            let total_seconds: i64 = self.periods
+                .iter()
+                .map(|period| period.get_seconds_between(reference_start, reference_end))
+                .sum();
+            let average_seconds = total_seconds / WTR_REFERENCE_PERIOD_WEEKS;
+
+            if average_seconds > WTR_MAX_WEEKLY_AVERAGE_SECONDS {
+                breaches.push(WorkingTimeBreach::WeeklyAverageExceeded { week_end, average_seconds });
+            }
+            week_end += TimeDelta::days(7);
+        }
+
+        breaches
+    }
+
+    fn check_daily_rest(&self, from: NaiveDate, to: NaiveDate) -> Vec<WorkingTimeBreach> {
+        let mut breaches = Vec::new();
+        let mut latest_finish: Option<NaiveDateTime> = None;
+
+        for period in &self.periods {
+            if let Some(finish) = latest_finish {
+                let rest_seconds = (period.start - finish).num_seconds().max(0);
+                let date = period.start.date();
+
+                if rest_seconds < WTR_DAILY_REST_SECONDS && date >= from && date <= to {
+                    breaches.push(WorkingTimeBreach::InsufficientDailyRest {
+                        finish,
+                        next_start: period.start,
+                        rest_seconds,
+                    });
+                }
+            }
+            latest_finish = Some(latest_finish.map_or(period.finish, |finish| finish.max(period.finish)));
+        }
+
+        breaches
+    }
+
+    // Checked fortnight by fortnight from the start of the period.
+    fn check_weekly_rest(&self, from: NaiveDate, to: NaiveDate) -> Vec<WorkingTimeBreach> {
+        let mut breaches = Vec::new();
+        let mut fortnight_start = from;
+
+        while fortnight_start + TimeDelta::days(13) <= to {
+            let start = fortnight_start.and_hms_opt(0, 0, 0).unwrap();
+            let middle = start + TimeDelta::days(7);
+            let end = start + TimeDelta::days(14);
+
+            let first_week = self.get_longest_rest_seconds(start, middle);
+            let second_week = self.get_longest_rest_seconds(middle, end);
+            let fortnight = self.get_longest_rest_seconds(start, end);
+
+            let weekly_rest_taken = first_week >= WTR_WEEKLY_REST_SECONDS && second_week >= WTR_WEEKLY_REST_SECONDS;
+            let fortnightly_rest_taken = fortnight >= WTR_FORTNIGHTLY_REST_SECONDS;
+
+            if !weekly_rest_taken && !fortnightly_rest_taken {
+                breaches.push(WorkingTimeBreach::InsufficientWeeklyRest {
+                    fortnight_start,
+                    longest_rest_seconds: fortnight,
+                });
+            }
+            fortnight_start += TimeDelta::days(14);
+        }
+
+        breaches
+    }
+
+    // Longest uninterrupted time off inside [from, to).
+    fn get_longest_rest_seconds(&self, from: NaiveDateTime, to: NaiveDateTime) -> i64 {
+        let mut longest = 0;
+        let mut cursor = from;
+
+        for period in self.periods.iter().filter(|p| p.finish > from && p.start < to) {
+            longest = longest.max((period.start - cursor).num_seconds());
+            cursor = cursor.max(period.finish);
+        }
+        longest.max((to - cursor).num_seconds())
+    }
+
+    // Uses the recorded break, or the job's break rules when none was recorded.
+    fn check_rest_breaks(&self, from: NaiveDate, to: NaiveDate) -> Vec<WorkingTimeBreach> {
+        self.periods
+            .iter()
+            .filter(|period| period.date >= from && period.date <= to)
+            .filter(|period| period.get_seconds() > WTR_BREAK_THRESHOLD_SECONDS)
+            .filter(|period| period.break_seconds < WTR_MIN_BREAK_SECONDS)
+            .map(|period| WorkingTimeBreach::MissingRestBreak {
+                date: period.date,
+                job_id: period.job_id,
+                shift_id: period.shift_id,
+                worked_seconds: period.get_seconds(),
+            })
+            .collect()
+    }
+}
+
+// ICALENDAR EXPORT
+
+// Dates without a time become all-day events, times are written as floating local time.
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum IcsTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+impl IcsTime {
+    fn to_property(self, name: &str) -> String {
+        match self {
+            IcsTime::Date(date) => format!("{name};VALUE=DATE:{}", date.format("%Y%m%d")),
+            IcsTime::DateTime(time) => format!("{name}:{}", time.format("%Y%m%dT%H%M%S")),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct IcsEvent {
+    // Stable between exports so calendar apps update the event instead of duplicating it.
+    uid: String,
+    start: IcsTime,
+    end: IcsTime,
+    summary: String,
+    description: String,
+}
+impl IcsEvent {
+    fn from_scheduled(job: &Job, scheduled: &ScheduledShift, estimated_pay: u32) -> Option<IcsEvent> {
+        if scheduled.status != ShiftStatus::ON { return None }
+
+        let (start, end) = match scheduled.start.zip(scheduled.finish) {
+            Some((start, finish)) => (IcsTime::DateTime(start), IcsTime::DateTime(finish)),
+            None => (IcsTime::Date(scheduled.date), IcsTime::Date(scheduled.date.succ_opt()?)),
+        };
+        let label = scheduled.label.as_deref().unwrap_or("Rota");
+
+        Some(IcsEvent {
+            uid: Self::get_uid(job, scheduled.date, 0),
+            start,
+            end,
+            summary: format!("{} - {}", job.name, label),
+            description: format!("Day {} of the cycle. Estimated pay: {}", scheduled.day_in_cycle, format_pence(estimated_pay)),
+        })
+    }
+    // nth counts earlier shifts on the same date, so the first one replaces the rota day's event.
+    fn from_shift(job: &Job, shift: &Shift, nth: usize, estimated_pay: u32) -> IcsEvent {
+        IcsEvent {
+            uid: Self::get_uid(job, shift.date, nth),
+            start: IcsTime::DateTime(shift.start),
+            end: IcsTime::DateTime(shift.finish),
+            summary: format!("{} - {:?}", job.name, shift.shift_type),
+            description: format!("Worked {}. Estimated pay: {}", shift.get_pretty_time_worked(), format_pence(estimated_pay)),
+        }
+    }
+    fn get_uid(job: &Job, date: NaiveDate, nth: usize) -> String {
+        match nth {
+            0 => format!("rota-{}-{}@wage-calculator", job.id, date.format("%Y%m%d")),
+            _ => format!("rota-{}-{}-{}@wage-calculator", job.id, date.format("%Y%m%d"), nth),
+        }
+    }
+    fn write_to(&self, out: &mut Vec<String>, stamp: &str) {
+        out.push("BEGIN:VEVENT".to_string());
+        out.push(format!("UID:{}", self.uid));
+        out.push(format!("DTSTAMP:{stamp}"));
+        out.push(self.start.to_property("DTSTART"));
+        out.push(self.end.to_property("DTEND"));
+        out.push(format!("SUMMARY:{}", Self::escape(&self.summary)));
+        out.push(format!("DESCRIPTION:{}", Self::escape(&self.description)));
+        out.push("END:VEVENT".to_string());
+    }
+    fn escape(text: &str) -> String {
+        text.replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace('\n', "\\n")
+    }
+}
+
+struct IcsExport {
+    events: Vec<IcsEvent>,
+}
+impl IcsExport {
+    // Confirmed shifts, plus rota days that don't have one logged yet.
+    fn for_jobs(
+        db: &Database,
+        jobs: &[&Job],
+        multipliers: &HashMap<i32, Vec<SalaryMultiplier>>,
+        overrides: &HashMap<i32, Vec<ScheduleOverride>>,
+        saved_holidays: &[BankHoliday],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<IcsExport, Error> {
+        let mut events = Vec::new();
+
+        for job in jobs {
+            let holidays = BankHolidayChecker::for_job_between(job, from, to, saved_holidays);
+            let job_multipliers = SalaryMultiplier::get_for(job, multipliers).unwrap_or_default();
+            let job_overrides = overrides.get(&job.id).map(Vec::as_slice).unwrap_or_default();
+            // Drafts go out as the rota day they were generated from.
+            let mut shifts = job.get_shifts_for_period_of(from, to, db)?;
+            shifts.retain(|shift| shift.is_confirmed);
+
+            for shift in &shifts {
+                let pay = ShiftPayment::new_for_shift(shift, job, db, job_multipliers.clone(), &holidays)?;
+                let nth = shifts.iter().filter(|other| other.date == shift.date && (other.start, other.id) < (shift.start, shift.id)).count();
+                events.push(IcsEvent::from_shift(job, shift, nth, ShiftPayment::get_total(&pay)));
+            }
+
+            for scheduled in job.scheduled_shifts(from, to, job_overrides) {
+                if shifts.iter().any(|shift| shift.date == scheduled.date) { continue }
+
+                let pay = ShiftPayment::forecast_for(&scheduled, job, db, job_multipliers.clone(), &holidays)?;
+                events.extend(IcsEvent::from_scheduled(job, &scheduled, ShiftPayment::get_total(&pay)));
+            }
+        }
+
+        Ok(IcsExport { events })
+    }
+
+    fn to_ics_string(&self) -> String {
+        let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//wage_calculator//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+        ];
+        for event in &self.events {
+            event.write_to(&mut lines, &stamp);
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        lines.iter().map(|line| Self::fold(line)).collect::<Vec<_>>().join("\r\n") + "\r\n"
+    }
+
+    // Lines longer than 75 octets continue on the next line after a space.
+    fn fold(line: &str) -> String {
+        let mut folded = String::new();
+        let mut length = 0;
+
+        for c in line.chars() {
+            if length + c.len_utf8() > 75 {
+                folded.push_str("\r\n ");
+                length = 1;
+            }
+            folded.push(c);
+            length += c.len_utf8();
+        }
+
+        folded
+    }
+
+    fn save(&self, path: &std::path::Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_ics_string())
+    }
+}
+
+// ICALENDAR IMPORT
+
+// First matching rule picks the shift type, anything unmatched is a normal scheduled shift.
+#[derive(Debug, Clone)]
+struct IcsMappingRule {
+    pattern: regex::Regex,
+    shift_type: ShiftType,
+}
+impl IcsMappingRule {
+    fn new(pattern: &str, shift_type: ShiftType) -> Result<IcsMappingRule, regex::Error> {
+        Ok(IcsMappingRule { pattern: regex::RegexBuilder::new(pattern).case_insensitive(true).build()?, shift_type })
+    }
+    // Plain text match on the summary, for users who don't want to write a regex.
+    fn containing(text: &str, shift_type: ShiftType) -> Result<IcsMappingRule, regex::Error> {
+        Self::new(&regex::escape(text), shift_type)
+    }
+    fn defaults() -> Vec<IcsMappingRule> {
+        [
+            (r"\bsick", ShiftType::Sick),
+            (r"\b(a/l|annual leave|holiday)\b", ShiftType::Holiday),
+            (r"\b(lieu|toil|paid leave)\b", ShiftType::PaidLeave),
+            (r"\b(unpaid|upl)\b", ShiftType::UnpaidAbsence),
+            (r"\b(overtime|o/t|extra)\b", ShiftType::ExtraShift),
+        ]
+        .into_iter()
+        .map(|(pattern, shift_type)| Self::new(pattern, shift_type).expect("Default rules are valid"))
+        .collect()
+    }
+    fn get_shift_type(rules: &[IcsMappingRule], summary: &str) -> ShiftType {
+        rules
+            .iter()
+            .find(|rule| rule.pattern.is_match(summary))
+            .map_or(ShiftType::Scheduled, |rule| rule.shift_type)
+    }
+}
+
+// A single VEVENT as read from the file, times already converted to UK local time.
+#[derive(PartialEq, Debug, Clone)]
+struct IcsImportEvent {
+    uid: String,
+    summary: String,
+    start: IcsTime,
+    end: IcsTime,
+    warning: Option<String>,
+    skip_reason: Option<String>, // e.g. a time zone we can't convert, the event is shown but not imported
+}
+impl IcsImportEvent {
+    fn parse_all(contents: &str) -> Vec<IcsImportEvent> {
+        let mut events = Vec::new();
+        let mut properties: Option<Vec<(String, String)>> = None;
+
+        for line in Self::unfold(contents) {
+            let Some((name, value)) = Self::split_property(&line) else { continue };
+            match (name, value) {
+                ("BEGIN", "VEVENT") => properties = Some(Vec::new()),
+                ("END", "VEVENT") => events.extend(properties.take().and_then(|props| Self::from_properties(&props))),
+                _ => if let Some(props) = properties.as_mut() { props.push((name.to_string(), value.to_string())) },
+            }
+        }
+
+        events
+    }
+
+    // The value starts at the first colon outside a quoted parameter, e.g. DTSTART;TZID="UTC+01:00":...
+    fn split_property(line: &str) -> Option<(&str, &str)> {
+        let mut is_quoted = false;
+        let (index, _) = line.char_indices().find(|(_, c)| {
+            if *c == '"' { is_quoted = !is_quoted }
+            *c == ':' && !is_quoted
+        })?;
+        Some((&line[..index], &line[index + 1..]))
+    }
+
+    // Lines starting with whitespace continue the line before.
+    fn unfold(contents: &str) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+
+        for line in contents.lines() {
+            match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+                (Some(rest), Some(last)) => last.push_str(rest),
+                _ => lines.push(line.to_string()),
+            }
+        }
+
+        lines
+    }
+
+    fn from_properties(properties: &[(String, String)]) -> Option<IcsImportEvent> {
+        let get = |name: &str| properties.iter().find(|(key, _)| key.split(';').next() == Some(name));
+
+        if get("STATUS").is_some_and(|(_, status)| status == "CANCELLED") { return None }
+
+        let mut warnings = Vec::new();
+        let mut skip_reason = None;
+        let (start_key, start_value) = get("DTSTART")?;
+        let start = Self::parse_time(start_key, start_value, &mut skip_reason)?;
+        let duration = get("DURATION").and_then(|(_, value)| Self::parse_duration(value));
+        let end = match (get("DTEND"), duration, start) {
+            (Some((key, value)), _, _) => Self::parse_time(key, value, &mut skip_reason)?,
+            (None, Some(duration), IcsTime::Date(date)) => IcsTime::Date(date + TimeDelta::days(duration.num_days().max(1))),
+            (None, Some(duration), IcsTime::DateTime(time)) => IcsTime::DateTime(time + duration),
+            // An all day event without an end is the one day.
+            (None, None, IcsTime::Date(date)) => IcsTime::Date(date.succ_opt()?),
+            (None, None, IcsTime::DateTime(time)) => {
+                skip_reason.get_or_insert_with(|| "No DTEND or DURATION, so the finish time isn't known".to_string());
+                IcsTime::DateTime(time)
+            },
+        };
+        if get("RRULE").is_some() {
+            warnings.push("Repeating events are imported as a single occurrence".to_string());
+        }
+
+        Some(IcsImportEvent {
+            uid: get("UID").map(|(_, uid)| uid.clone()).unwrap_or_default(),
+            summary: get("SUMMARY").map(|(_, summary)| Self::unescape(summary)).unwrap_or_default(),
+            start,
+            end,
+            warning: (!warnings.is_empty()).then(|| warnings.join(", ")),
+            skip_reason,
+        })
+    }
+
+    // UTC times are moved onto UK local time, Europe/London and floating times are already local.
+    // Any other time zone would need its rules, so the event is skipped rather than guessed at.
+    fn parse_time(key: &str, value: &str, skip_reason: &mut Option<String>) -> Option<IcsTime> {
+        let params: Vec<&str> = key.split(';').skip(1).collect();
+
+        if params.contains(&"VALUE=DATE") || value.len() == 8 {
+            return NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(IcsTime::Date)
+        }
+
+        if let Some(utc) = value.strip_suffix('Z') {
+            let utc = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+            return Some(IcsTime::DateTime(uk_local_from_utc(utc)))
+        }
+
+        let time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+        match params.iter().find_map(|param| param.strip_prefix("TZID=")).map(|tzid| tzid.trim_matches('"')) {
+            None | Some("Europe/London" | "GB" | "GMT Standard Time") => Some(IcsTime::DateTime(time)),
+            Some("UTC" | "Etc/UTC" | "GMT" | "Etc/GMT") => Some(IcsTime::DateTime(uk_local_from_utc(time))),
+            Some(tzid) => {
+                *skip_reason = Some(format!("Times in {tzid} aren't supported"));
+                Some(IcsTime::DateTime(time))
+            },
+        }
+    }
+
+    // e.g. PT8H30M, P1D or P1W, a negative duration can't be the end of a shift.
+    fn parse_duration(value: &str) -> Option<TimeDelta> {
+        let rest = value.strip_prefix('+').unwrap_or(value).strip_prefix('P')?;
+        let mut total = TimeDelta::zero();
+        let mut number = String::new();
+
+        for c in rest.chars() {
+            match c {
+                '0'..='9' => number.push(c),
+                'T' if number.is_empty() => {},
+                unit => {
+                    let amount: i64 = number.parse().ok()?;
+                    number.clear();
+                    total += match unit {
+                        'W' => TimeDelta::weeks(amount),
+                        'D' => TimeDelta::days(amount),
+                        'H' => TimeDelta::hours(amount),
+                        'M' => TimeDelta::minutes(amount),
+                        'S' => TimeDelta::seconds(amount),
+                        _ => return None,
+                    };
+                },
+            }
+        }
+
+        number.is_empty().then_some(total)
+    }
+
+    fn unescape(text: &str) -> String {
+        text.replace("\\n", "\n")
+            .replace("\\N", "\n")
+            .replace("\\,", ",")
+            .replace("\\;", ";")
+            .replace("\\\\", "\\")
+    }
+}
+
+// BST runs from 01:00 UTC on the last Sunday of March to 01:00 UTC on the last Sunday of October.
+fn uk_local_from_utc(utc: NaiveDateTime) -> NaiveDateTime {
+    let last_sunday = |month: u32| {
+        let last_day = NaiveDate::from_ymd_opt(utc.year(), month + 1, 1).unwrap().pred_opt().unwrap();
+        last_day - TimeDelta::days(last_day.weekday().num_days_from_sunday() as i64)
+    };
+    let one_am = NaiveTime::from_hms_opt(1, 0, 0).unwrap();
+    let bst_start = last_sunday(3).and_time(one_am);
+    let bst_end = last_sunday(10).and_time(one_am);
+
+    if utc >= bst_start && utc < bst_end { utc + TimeDelta::hours(1) } else { utc }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum ImportStatus {
+    New,
+    Changed(i32), // id of the existing shift from the same event
+    Duplicate(i32),
+    Skipped, // the warning says why
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct IcsImportRow {
+    uid: String,
+    summary: String,
+    date: NaiveDate,
+    shift_type: ShiftType,
+    start: NaiveDateTime,
+    finish: NaiveDateTime,
+    status: ImportStatus,
+    warning: Option<String>,
+}
+
+// What would be saved, checked against the job's existing shifts before anything is written.
+struct IcsImportPreview {
+    job_id: i32,
+    rows: Vec<IcsImportRow>,
+}
+impl IcsImportPreview {
+    fn new(
+        db: &Database,
+        job: &Job,
+        contents: &str,
+        rules: &[IcsMappingRule],
+        overrides: &[ScheduleOverride],
+    ) -> Result<IcsImportPreview, Error> {
+        let mut rows = Vec::new();
+
+        for event in IcsImportEvent::parse_all(contents) {
+            let shift_type = IcsMappingRule::get_shift_type(rules, &event.summary);
+
+            match (event.start, event.end) {
+                (IcsTime::DateTime(start), IcsTime::DateTime(finish)) => {
+                    rows.push(Self::new_row(&event, start.date(), shift_type, start, finish.max(start)));
+                },
+                // All day events cover every date up to DTEND, using the rota times where there are some.
+                (start, end) => {
+                    let first = match start { IcsTime::Date(date) => date, IcsTime::DateTime(time) => time.date() };
+                    let last = match end { IcsTime::Date(date) => date.pred_opt().unwrap_or(date), IcsTime::DateTime(time) => time.date() };
+
+                    for date in first.iter_days().take_while(|date| *date <= last.max(first)) {
+                        let scheduled = job.get_scheduled_shift_on(date, overrides);
+                        let midnight = date.and_time(NaiveTime::MIN);
+                        match scheduled.and_then(|scheduled| scheduled.start.zip(scheduled.finish)) {
+                            Some((start, finish)) => rows.push(Self::new_row(&event, date, shift_type, start, finish)),
+                            None => {
+                                let mut row = Self::new_row(&event, date, shift_type, midnight, midnight);
+                                row.status = ImportStatus::Skipped;
+                                row.warning = Some("All day event with no rota times for this day".to_string());
+                                rows.push(row);
+                            },
+                        }
+                    }
+                },
+            }
+        }
+
+        let from = rows.iter().map(|row| row.date).min();
+        let to = rows.iter().map(|row| row.date).max();
+        let existing = match from.zip(to) {
+            Some((from, to)) => job.get_shifts_for_period_of(from, to, db)?,
+            None => Vec::new(),
+        };
+        for row in rows.iter_mut().filter(|row| row.status != ImportStatus::Skipped) {
+            row.status = Self::get_status(row, &existing);
+        }
+
+        Ok(IcsImportPreview { job_id: job.id, rows })
+    }
+
+    fn new_row(event: &IcsImportEvent, date: NaiveDate, shift_type: ShiftType, start: NaiveDateTime, finish: NaiveDateTime) -> IcsImportRow {
+        IcsImportRow {
+            uid: event.uid.clone(),
+            summary: event.summary.clone(),
+            date,
+            shift_type,
+            start,
+            finish,
+            status: if event.skip_reason.is_some() { ImportStatus::Skipped } else { ImportStatus::New },
+            warning: event.skip_reason.clone().or_else(|| event.warning.clone()),
+        }
+    }
+
+    // Same event imported before, or a shift entered by hand that overlaps it.
+    fn get_status(row: &IcsImportRow, existing: &[Shift]) -> ImportStatus {
+        let from_same_event = existing
+            .iter()
+            .find(|shift| shift.date == row.date && !row.uid.is_empty() && shift.source_uid.as_deref() == Some(row.uid.as_str()));
+
+        if let Some(shift) = from_same_event {
+            let unchanged = shift.start == row.start && shift.finish == row.finish && shift.shift_type == row.shift_type;
+            return if unchanged { ImportStatus::Duplicate(shift.id) } else { ImportStatus::Changed(shift.id) }
+        }
+
+        let overlapping = existing.iter().find(|shift| {
+            shift.date == row.date && (shift.start < row.finish && row.start < shift.finish || shift.start == row.start)
+        });
+        match overlapping {
+            Some(shift) => ImportStatus::Duplicate(shift.id),
+            None => ImportStatus::New,
+        }
+    }
+
+    // Saves new rows and updates changed ones, duplicates are left alone.
+    fn import(&self, db: &Database, id_gen: &IdGenerator) -> Result<usize, Error> {
+        let rw = db.rw_transaction()?;
+        let mut imported = 0;
+
+        for row in &self.rows {
+            match row.status {
+                ImportStatus::Duplicate(_) | ImportStatus::Skipped => continue,
+                // Only what the calendar knows about is updated, the break and clock times are kept.
+                ImportStatus::Changed(id) => {
+                    let Some(mut shift) = rw.get().primary::<Shift>(id)? else { continue };
+                    shift.shift_type = row.shift_type;
+                    shift.start = row.start;
+                    shift.finish = row.finish;
+                    shift.segments = Vec::new();
+                    rw.upsert(shift)?;
+                },
+                ImportStatus::New => {
+                    let shift = Shift::new(id_gen.next_shift_id(), self.job_id, row.date, row.shift_type, row.start, row.finish)
+                        .with_source_uid(row.uid.clone());
+                    rw.insert(shift)?;
+                },
+            }
+            imported += 1;
+        }
+
+        rw.commit()?;
+        Ok(imported)
+    }
+}
+
+// e.g. 12345 -> £123.45
+fn format_pence(pence: u32) -> String {
+    format!("£{}.{:02}", pence / 100, pence % 100)
+}
+
+// e.g. "123.45" or "£123.45" -> 12345, None if it isn't an amount in pounds.
+fn parse_pence(text: &str) -> Option<u32> {
+    let text = text.trim().trim_start_matches('£');
+    let (pounds, pence) = text.split_once('.').unwrap_or((text, "0"));
+    if pence.is_empty() || pence.len() > 2 || !pence.chars().all(|c| c.is_ascii_digit()) { return None }
+
+    let pounds: u32 = pounds.parse().ok()?;
+    let pence: u32 = format!("{pence:0<2}").parse().ok()?;
+    pounds.checked_mul(100)?.checked_add(pence)
 }
 
 
@@ -1432,6 +6887,8 @@ pub struct IdGenerator {
     deduction_counter: AtomicI32,
     custom_payment_counter: AtomicI32,
     salary_multiplier_counter: AtomicI32,
+    schedule_override_counter: AtomicI32,
+    bank_holiday_counter: AtomicI32,
 }
 
 trait HasId {
@@ -1450,7 +6907,27 @@ impl HasId for SalaryMultiplier {
 }
 impl HasCounter for SalaryMultiplier {
     fn get_counter(generator: &IdGenerator) -> &AtomicI32 {
-        &generator.deduction_counter
+        &generator.salary_multiplier_counter
+    }
+}
+impl HasId for ScheduleOverride {
+    fn id(&self) -> i32 {
+        self.id
+    }
+}
+impl HasCounter for ScheduleOverride {
+    fn get_counter(generator: &IdGenerator) -> &AtomicI32 {
+        &generator.schedule_override_counter
+    }
+}
+impl HasId for BankHoliday {
+    fn id(&self) -> i32 {
+        self.id
+    }
+}
+impl HasCounter for BankHoliday {
+    fn get_counter(generator: &IdGenerator) -> &AtomicI32 {
+        &generator.bank_holiday_counter
     }
 }
 impl HasId for Deduction {
@@ -1505,7 +6982,9 @@ impl IdGenerator {
             job_counter: AtomicI32::new(Self::get_max_id::<Job>(db)?),
             deduction_counter: AtomicI32::new(Self::get_max_id::<Deduction>(db)?),
             custom_payment_counter: AtomicI32::new(Self::get_max_id::<CustomShiftPaymentType>(db)?),
-            salary_multiplier_counter: AtomicI32::new(Self::get_max_id::<SalaryMultiplier>(db)?)
+            salary_multiplier_counter: AtomicI32::new(Self::get_max_id::<SalaryMultiplier>(db)?),
+            schedule_override_counter: AtomicI32::new(Self::get_max_id::<ScheduleOverride>(db)?),
+            bank_holiday_counter: AtomicI32::new(Self::get_max_id::<BankHoliday>(db)?),
         })
     }
     fn get_max_id<T>(db: &Database) -> Result<i32, Error> where T: HasId + native_db::ToInput,{
@@ -1545,6 +7024,12 @@ impl IdGenerator {
     pub fn next_salary_multiplier_id(&self) -> i32 {
         self.next_id::<SalaryMultiplier>()
     }
+    pub fn next_schedule_override_id(&self) -> i32 {
+        self.next_id::<ScheduleOverride>()
+    }
+    pub fn next_bank_holiday_id(&self) -> i32 {
+        self.next_id::<BankHoliday>()
+    }
 
 }
 
@@ -1565,14 +7050,254 @@ impl BankHolidayChecker {
         if !self.is_bank_holiday(date) { return None }
         self.holidays.iter().find(|holiday| holiday.date == date)
     }
+
+    // The job's region for the year with the user's bank holidays merged over it,
+    // the ones for every job first so a job's own entries win.
+    fn for_job(job: &Job, year: i32, saved: &[BankHoliday]) -> BankHolidayChecker {
+        let applicable = saved.iter().filter(|holiday| holiday.applies_to(job)).collect();
+        Self::merged(job.get_region(), year, applicable)
+    }
+    // The calendar every job in the region shares, without any job's own entries.
+    fn for_region_with(region: UKRegion, year: i32, saved: &[BankHoliday]) -> BankHolidayChecker {
+        let applicable = saved
+            .iter()
+            .filter(|holiday| holiday.job_id.is_none() && holiday.region.is_none_or(|r| r == region))
+            .collect();
+        Self::merged(region, year, applicable)
+    }
+    fn merged(region: UKRegion, year: i32, mut applicable: Vec<&BankHoliday>) -> BankHolidayChecker {
+        let mut holidays = Self::for_region(region, year).holidays;
+
+        applicable.retain(|holiday| holiday.date.year() == year);
+        applicable.sort_by_key(|holiday| (holiday.job_id.is_some(), holiday.id));
+
+        for holiday in applicable {
+            holidays.retain(|existing| existing.date != holiday.date);
+            if holiday.is_observed {
+                holidays.push(holiday.clone());
+            }
+        }
+
+        holidays.sort_by_key(|holiday| holiday.date);
+        BankHolidayChecker::new(holidays)
+    }
+    // Every year a period touches, e.g. a payslip running from December into January.
+    fn for_job_between(job: &Job, from: NaiveDate, to: NaiveDate, saved: &[BankHoliday]) -> BankHolidayChecker {
+        let holidays = (from.year()..=to.year()).flat_map(|year| Self::for_job(job, year, saved).holidays).collect();
+        BankHolidayChecker::new(holidays)
+    }
+
+    // The standard calendar, one-off holidays (jubilees, coronations, moved VE Day)
+    // aren't included as they can't be worked out from the year.
+    fn for_region(region: UKRegion, year: i32) -> BankHolidayChecker {
+        let date = |month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let easter = Self::get_easter_sunday(year);
+
+        let mut fixed = vec![(date(1, 1), "New Year's Day")];
+        let mut holidays = vec![
+            BankHoliday::new(easter - TimeDelta::days(2), "Good Friday".to_string()),
+            BankHoliday::new(Self::get_first_monday(year, 5), "Early May bank holiday".to_string()),
+            BankHoliday::new(Self::get_last_monday(year, 5), "Spring bank holiday".to_string()),
+        ];
+
+        match region {
+            UKRegion::Scotland => {
+                fixed.push((date(1, 2), "2nd January"));
+                fixed.push((date(11, 30), "St Andrew's Day"));
+                holidays.push(BankHoliday::new(Self::get_first_monday(year, 8), "Summer bank holiday".to_string()));
+            },
+            UKRegion::England | UKRegion::Wales | UKRegion::NorthernIreland => {
+                holidays.push(BankHoliday::new(easter + TimeDelta::days(1), "Easter Monday".to_string()));
+                holidays.push(BankHoliday::new(Self::get_last_monday(year, 8), "Summer bank holiday".to_string()));
+            },
+        }
+        if region == UKRegion::NorthernIreland {
+            fixed.push((date(3, 17), "St Patrick's Day"));
+            fixed.push((date(7, 12), "Battle of the Boyne (Orangemen's Day)"));
+        }
+        fixed.push((date(12, 25), "Christmas Day"));
+        fixed.push((date(12, 26), "Boxing Day"));
+
+        // Weekday holidays are fixed first so a substitute never lands on one,
+        // e.g. Christmas on a Sunday is moved to the Tuesday after Boxing Day.
+        let is_weekend = |date: NaiveDate| matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        holidays.extend(fixed.iter().filter(|(date, _)| !is_weekend(*date)).map(|(date, name)| BankHoliday::new(*date, name.to_string())));
+
+        for (date, name) in fixed.iter().filter(|(date, _)| is_weekend(*date)) {
+            let substitute = date
+                .iter_days()
+                .find(|day| !is_weekend(*day) && !holidays.iter().any(|holiday| holiday.date == *day))
+                .unwrap();
+            holidays.push(BankHoliday::new(substitute, format!("{name} (substitute day)")));
+        }
+
+        holidays.sort_by_key(|holiday| holiday.date);
+        BankHolidayChecker::new(holidays)
+    }
+
+    // Anonymous Gregorian computus.
+    fn get_easter_sunday(year: i32) -> NaiveDate {
+        let a = year % 19;
+        let b = year / 100;
+        let c = year % 100;
+        let d = b / 4;
+        let e = b % 4;
+        let f = (b + 8) / 25;
+        let g = (b - f + 1) / 3;
+        let h = (19 * a + b - d - g + 15) % 30;
+        let i = c / 4;
+        let k = c % 4;
+        let l = (32 + 2 * e + 2 * i - h - k) % 7;
+        let m = (a + 11 * h + 22 * l) / 451;
+        let month = (h + l - 7 * m + 114) / 31;
+        let day = (h + l - 7 * m + 114) % 31 + 1;
+
+        NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+    }
+    fn get_first_monday(year: i32, month: u32) -> NaiveDate {
+        NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Mon, 1).unwrap()
+    }
+    fn get_last_monday(year: i32, month: u32) -> NaiveDate {
+        let next_month = if month == 12 { NaiveDate::from_ymd_opt(year + 1, 1, 1) } else { NaiveDate::from_ymd_opt(year, month + 1, 1) };
+        let last_day = next_month.unwrap().pred_opt().unwrap();
+        last_day - TimeDelta::days(last_day.weekday().num_days_from_monday() as i64)
+    }
 }
+// Computed holidays have id 0, saved ones are the user's own additions and
+// changes, e.g. a coronation or an employer paying Christmas Eve as a bank holiday.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[native_model(id = 10, version = 1)]
+#[native_db]
 struct BankHoliday {
+    #[primary_key]
+    id: i32,
+    job_id: Option<i32>, // None = every job
+    region: Option<UKRegion>, // None = every region
     date: NaiveDate,
     name: String,
+    // false takes the day out of the calendar, e.g. an employer that works Easter Monday as normal.
+    is_observed: bool,
 }
 impl BankHoliday {
     fn new(date: NaiveDate, name: String) -> BankHoliday {
-        BankHoliday { date: date, name: name }
+        BankHoliday { id: 0, job_id: None, region: None, date, name, is_observed: true }
+    }
+    fn new_custom(
+        id_gen: &IdGenerator,
+        job_id: Option<i32>,
+        region: Option<UKRegion>,
+        date: NaiveDate,
+        name: String,
+        is_observed: bool,
+    ) -> BankHoliday {
+        BankHoliday { id: id_gen.next_bank_holiday_id(), job_id, region, date, name, is_observed }
+    }
+    fn load_all(db: &Database) -> Result<Vec<BankHoliday>, Error> {
+        let r = db.r_transaction()?;
+
+        let mut holidays: Vec<BankHoliday> = r
+            .scan()
+            .primary()?
+            .all()?
+            .collect::<Result<Vec<_>, _>>()?;
+        holidays.sort_by_key(|holiday| holiday.date);
+
+        Ok(holidays)
+    }
+    fn applies_to(&self, job: &Job) -> bool {
+        self.job_id.is_none_or(|id| id == job.id) && self.region.is_none_or(|region| region == job.get_region())
+    }
+}
+
+// The official feed saved from https://www.gov.uk/bank-holidays.json, grouped by division.
+#[derive(Deserialize, Debug)]
+struct GovUkDivision {
+    events: Vec<GovUkEvent>,
+}
+#[derive(Deserialize, Debug)]
+struct GovUkEvent {
+    title: String,
+    date: NaiveDate,
+    #[serde(default)]
+    notes: String,
+}
+impl GovUkEvent {
+    // e.g. "Boxing Day (substitute day)", matching the computed names.
+    fn get_name(&self) -> String {
+        if self.notes.is_empty() { return self.title.clone() }
+        format!("{} ({})", self.title, self.notes.to_lowercase())
+    }
+}
+
+#[derive(Default)]
+struct BankHolidayImport {
+    added: Vec<BankHoliday>,
+    removed: Vec<BankHoliday>, // Saved as not observed
+    renamed: Vec<(String, BankHoliday)>, // Old name and the official holiday
+}
+impl BankHolidayImport {
+    // gov.uk writes "New Year’s Day" with a curly apostrophe, the computed names use a straight one.
+    fn is_same_name(a: &str, b: &str) -> bool {
+        a.replace('’', "'") == b.replace('’', "'")
+    }
+    // Compares each region's calendar with the official one for every year the file covers,
+    // the differences are saved so the merged calendar matches it.
+    fn from_gov_uk_json(db: &Database, id_gen: &IdGenerator, contents: &str) -> Result<BankHolidayImport, Box<dyn std::error::Error>> {
+        let divisions: HashMap<String, GovUkDivision> = serde_json::from_str(contents)?;
+        let saved = BankHoliday::load_all(db)?;
+        let mut import = BankHolidayImport::default();
+
+        for (division, official) in &divisions {
+            let regions: &[UKRegion] = match division.as_str() {
+                "england-and-wales" => &[UKRegion::England, UKRegion::Wales],
+                "scotland" => &[UKRegion::Scotland],
+                "northern-ireland" => &[UKRegion::NorthernIreland],
+                _ => continue,
+            };
+            let years: HashSet<i32> = official.events.iter().map(|event| event.date.year()).collect();
+
+            for region in regions {
+                for year in &years {
+                    let current = BankHolidayChecker::for_region_with(*region, *year, &saved);
+                    let events: Vec<&GovUkEvent> = official.events.iter().filter(|event| event.date.year() == *year).collect();
+
+                    for event in &events {
+                        match current.get_holiday_on(event.date) {
+                            Some(existing) if Self::is_same_name(&existing.name, &event.get_name()) => {},
+                            Some(existing) => {
+                                let holiday = Self::get_saved_for(&saved, id_gen, *region, event.date, event.get_name(), true);
+                                import.renamed.push((existing.name.clone(), holiday));
+                            },
+                            None => import.added.push(Self::get_saved_for(&saved, id_gen, *region, event.date, event.get_name(), true)),
+                        }
+                    }
+                    for existing in &current.holidays {
+                        if events.iter().any(|event| event.date == existing.date) { continue }
+                        import.removed.push(Self::get_saved_for(&saved, id_gen, *region, existing.date, existing.name.clone(), false));
+                    }
+                }
+            }
+        }
+
+        let rw = db.rw_transaction()?;
+        for holiday in import.added.iter().chain(import.removed.iter()).chain(import.renamed.iter().map(|(_, holiday)| holiday)) {
+            rw.upsert(holiday.clone())?;
+        }
+        rw.commit()?;
+
+        Ok(import)
+    }
+
+    // Reuses the region's saved entry for the date so importing again doesn't pile up duplicates.
+    fn get_saved_for(saved: &[BankHoliday], id_gen: &IdGenerator, region: UKRegion, date: NaiveDate, name: String, is_observed: bool) -> BankHoliday {
+        let existing = saved
+            .iter()
+            .find(|holiday| holiday.job_id.is_none() && holiday.region == Some(region) && holiday.date == date);
+
+        match existing {
+            Some(existing) => BankHoliday { name, is_observed, ..existing.clone() },
+            None => BankHoliday::new_custom(id_gen, None, Some(region), date, name, is_observed),
+        }
     }
 }
 
@@ -1612,4 +7337,771 @@ impl Persistable for Job {}
 impl Persistable for Shift {}
 impl Persistable for Deduction {}
 impl Persistable for SalaryMultiplier {}
-impl Persistable for CustomShiftPaymentType {}
\ No newline at end of file
+impl Persistable for CustomShiftPaymentType {}
+impl Persistable for ScheduleOverride {}
+impl Persistable for BankHoliday {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> Database<'static> {
+        Builder::new().create_in_memory(&MODELS).unwrap()
+    }
+    fn test_job() -> Job {
+        Job {
+            id: 1,
+            name: "Test".to_string(),
+            basic_pay: 1200,
+            base_pay_period_hours: None,
+            shift_pattern: None,
+            first_day: None,
+            fixed_start_time: None,
+            fixed_shift_duration: None,
+            tax_week_start: None,
+            break_rules: Vec::new(),
+            clock_rounding: None,
+            salary: None,
+            split_shift_allowance: None,
+            region: None,
+            lieu_policy: None,
+            student_loans: Vec::new(),
+            pension: None,
+        }
+    }
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y-%m-%d %H:%M").unwrap()
+    }
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+    fn shift(id: i32, day: &str, start: &str, finish: &str) -> Shift {
+        Shift::new(id, 1, date(day), ShiftType::Scheduled, at(day, start), at(day, finish))
+    }
+    fn payment(amount: u32, payment_type: ShiftPaymentType, seconds: i64) -> ShiftPayment {
+        ShiftPayment { shift_id: Some(1), job_id: 1, amount, payment_type, deductions: None, seconds }
+    }
+    fn summary(db: &Database, job: &Job, from: &str, to: &str) -> PaymentSummary {
+        PaymentSummary::new(date(from), date(to), job, db, &HashMap::new(), &[]).unwrap()
+    }
+    fn multiplier(id: i32, value: i32, schedule: ReocurrementSchedule) -> SalaryMultiplier {
+        SalaryMultiplier {
+            id,
+            job_id: 1,
+            behavior: MultiplierBehavior::Compound,
+            priority: MultiplierPriority::Medium,
+            name: format!("x{}", value as f32 / 1000.0),
+            description: None,
+            schedule,
+            multiplier: Multiplier { value },
+            time_window: None,
+            premium_period: None,
+        }
+    }
+    fn sundays() -> ReocurrementSchedule {
+        ReocurrementSchedule::Weekly { weekdays: vec![Weekday::Sun], start_date: date("2026-01-01"), end_date: None }
+    }
+    fn rotating_job() -> Job {
+        // 2 on, 2 off from Monday 5 October 2026, 09:00-17:00.
+        Job {
+            shift_pattern: Some(ShiftPattern::Rotation(vec![RotationRun::on(2), RotationRun::off(2)])),
+            first_day: Some(date("2026-10-05")),
+            fixed_start_time: NaiveTime::from_hms_opt(9, 0, 0),
+            fixed_shift_duration: Some(TimeDelta::hours(8)),
+            ..test_job()
+        }
+    }
+    fn schedule_override(id: i32, day: &str, change: ScheduleChange) -> ScheduleOverride {
+        ScheduleOverride { id, job_id: 1, date: date(day), change }
+    }
+
+    #[test]
+    fn short_rest_and_missing_breaks_are_breaches() {
+        let db = test_db();
+        let job = test_job();
+        shift(1, "2026-10-12", "16:00", "22:00").save(&db).unwrap();
+        shift(2, "2026-10-13", "06:00", "12:00").save(&db).unwrap();
+        shift(3, "2026-10-14", "09:00", "17:00").save(&db).unwrap();
+
+        let (from, to) = (date("2026-10-12"), date("2026-10-14"));
+        let breaches = WorkingTimeChecker::load(&db, &[&job], from, to).unwrap().check(from, to);
+
+        assert_eq!(breaches, vec![
+            WorkingTimeBreach::InsufficientDailyRest {
+                finish: at("2026-10-12", "22:00"),
+                next_start: at("2026-10-13", "06:00"),
+                rest_seconds: 8 * 3600,
+            },
+            WorkingTimeBreach::MissingRestBreak { date: date("2026-10-14"), job_id: 1, shift_id: Some(3), worked_seconds: 8 * 3600 },
+        ]);
+    }
+
+    #[test]
+    fn breaks_are_deducted_by_the_longest_matching_rule() {
+        let job = Job {
+            break_rules: vec![
+                BreakRule { min_shift_duration: TimeDelta::hours(6), unpaid_break: TimeDelta::minutes(30) },
+                BreakRule { min_shift_duration: TimeDelta::hours(10), unpaid_break: TimeDelta::hours(1) },
+            ],
+            ..test_job()
+        };
+
+        assert_eq!(shift(1, "2026-10-12", "09:00", "14:00").get_paid_time(&job), TimeDelta::hours(5));
+        assert_eq!(shift(1, "2026-10-12", "09:00", "17:00").get_paid_time(&job), TimeDelta::minutes(7 * 60 + 30));
+        assert_eq!(shift(1, "2026-10-12", "08:00", "19:00").get_paid_time(&job), TimeDelta::hours(10));
+
+        // A break that was actually taken replaces the rules.
+        let mut taken = shift(1, "2026-10-12", "09:00", "17:00");
+        taken.unpaid_break = Some(ShiftBreak { start: None, duration: TimeDelta::minutes(15) });
+        assert_eq!(taken.get_paid_time(&job), TimeDelta::minutes(7 * 60 + 45));
+    }
+
+    #[test]
+    fn rounding_rules_round_within_the_interval() {
+        let rule = |direction, grace_minutes| RoundingRule { interval_minutes: 15, direction, grace_minutes };
+
+        assert_eq!(rule(RoundingDirection::Nearest, 0).apply(at("2026-10-12", "09:07")), at("2026-10-12", "09:00"));
+        assert_eq!(rule(RoundingDirection::Nearest, 0).apply(at("2026-10-12", "09:08")), at("2026-10-12", "09:15"));
+        assert_eq!(rule(RoundingDirection::Up, 0).apply(at("2026-10-12", "09:01")), at("2026-10-12", "09:15"));
+        assert_eq!(rule(RoundingDirection::Down, 0).apply(at("2026-10-12", "17:14")), at("2026-10-12", "17:00"));
+        assert_eq!(rule(RoundingDirection::Up, 5).apply(at("2026-10-12", "09:03")), at("2026-10-12", "09:00"));
+        assert_eq!(rule(RoundingDirection::Up, 5).apply(at("2026-10-12", "09:06")), at("2026-10-12", "09:15"));
+        assert_eq!(rule(RoundingDirection::Exact, 5).apply(at("2026-10-12", "08:57")), at("2026-10-12", "09:00"));
+        assert_eq!(rule(RoundingDirection::Exact, 5).apply(at("2026-10-12", "08:52")), at("2026-10-12", "08:52"));
+    }
+
+    #[test]
+    fn daily_overtime_keeps_the_day_type() {
+        let mut payments = vec![
+            payment(1000, ShiftPaymentType::Sunday, 36_000),
+            payment(300, ShiftPaymentType::UnsociableSunday, 0),
+        ];
+
+        let overtime = ShiftPayment::split_daily_overtime(&mut payments, 36_000, 28_800);
+
+        assert_eq!(overtime, vec![payment(200, ShiftPaymentType::SundayOvertime, 7_200)]);
+        assert_eq!(payments, vec![
+            payment(800, ShiftPaymentType::Sunday, 28_800),
+            payment(300, ShiftPaymentType::UnsociableSunday, 0),
+        ]);
+        assert!(ShiftPayment::split_daily_overtime(&mut payments, 28_800, 28_800).is_empty());
+    }
+
+    #[test]
+    fn tips_are_kept_out_of_earnings() {
+        let db = test_db();
+        let id_gen = IdGenerator::new(&db).unwrap();
+        let job = test_job();
+        let worked = shift(id_gen.next_shift_id(), "2026-10-12", "09:00", "17:00").saved(&db).unwrap();
+        CustomShiftPaymentType::new_tip(&id_gen, &worked, "Card tips".to_string(), IncomeType::CardTips, 2_500, false).save(&db).unwrap();
+        CustomShiftPaymentType::new_tip(&id_gen, &worked, "Tronc".to_string(), IncomeType::Tronc, 1_000, true).save(&db).unwrap();
+
+        let summary = summary(&db, &job, "2026-10-12", "2026-10-18");
+
+        assert_eq!(summary.get_earnings(), 9_600);
+        assert_eq!(summary.get_tronc_total(), 3_500);
+        assert_eq!(summary.get_taxable_pay(), 13_100);
+        // The troncmaster's share has no NI.
+        assert_eq!(summary.get_niable_pay(), 12_100);
+    }
+
+    #[test]
+    fn salary_is_prorated_for_a_starter() {
+        let salary = |proration| Salary {
+            annual_salary: 1_200_000,
+            contracted_minutes_per_week: NonZeroU32::new(2250).unwrap(),
+            frequency: PayFrequency::Monthly,
+            proration,
+            start_date: Some(date("2026-06-16")),
+            end_date: None,
+        };
+        let job = test_job();
+        let (from, to) = (date("2026-06-01"), date("2026-06-30"));
+
+        // 15 of 30 calendar days.
+        assert_eq!(salary(ProrationMethod::CalendarDays).get_pay_for(&job, from, to, &HashSet::new(), &[]), 50_000);
+        // 11 of 22 weekdays, then 10 with a day's unpaid absence.
+        assert_eq!(salary(ProrationMethod::WorkingDays).get_pay_for(&job, from, to, &HashSet::new(), &[]), 50_000);
+        let absences = HashSet::from([date("2026-06-17")]);
+        assert_eq!(salary(ProrationMethod::WorkingDays).get_pay_for(&job, from, to, &absences, &[]), 45_454);
+    }
+
+    #[test]
+    fn salary_pays_a_week_with_no_days_on() {
+        let salary = Salary {
+            annual_salary: 1_040_000,
+            contracted_minutes_per_week: NonZeroU32::new(2400).unwrap(),
+            frequency: PayFrequency::Weekly,
+            proration: ProrationMethod::WorkingDays,
+            start_date: None,
+            end_date: None,
+        };
+        // 7 on, 7 off, Sunday to Saturday like the tax week.
+        let job = Job {
+            shift_pattern: Some(ShiftPattern::Rotation(vec![RotationRun::on(7), RotationRun::off(7)])),
+            first_day: Some(date("2026-10-04")),
+            salary: Some(salary),
+            ..test_job()
+        };
+
+        assert_eq!(salary.get_pay_for(&job, date("2026-10-04"), date("2026-10-10"), &HashSet::new(), &[]), 20_000);
+        assert_eq!(salary.get_pay_for(&job, date("2026-10-11"), date("2026-10-17"), &HashSet::new(), &[]), 20_000);
+    }
+
+    #[test]
+    fn salaried_shifts_only_pay_the_premium() {
+        let db = test_db();
+        // £10 an hour over 40 hours a week.
+        let job = Job {
+            salary: Some(Salary {
+                annual_salary: 2_080_000,
+                contracted_minutes_per_week: NonZeroU32::new(2400).unwrap(),
+                frequency: PayFrequency::Monthly,
+                proration: ProrationMethod::CalendarDays,
+                start_date: None,
+                end_date: None,
+            }),
+            ..test_job()
+        };
+        let multipliers = HashMap::from([(1, vec![multiplier(1, 1500, sundays())])]);
+        shift(1, "2026-10-11", "09:00", "17:00").save(&db).unwrap();
+        shift(2, "2026-10-12", "09:00", "17:00").save(&db).unwrap();
+
+        let summary = PaymentSummary::new(date("2026-10-01"), date("2026-10-31"), &job, &db, &multipliers, &[]).unwrap();
+
+        let types: Vec<(&ShiftPaymentType, u32)> = summary.shift_payments.iter().map(|payment| (&payment.payment_type, payment.amount)).collect();
+        assert_eq!(types, vec![(&ShiftPaymentType::Salary, 173_333), (&ShiftPaymentType::Sunday, 4_000)]);
+    }
+
+    #[test]
+    fn rotation_repeats_forwards_and_backwards() {
+        let job = rotating_job();
+        let status = |day: &str| job.get_scheduled_shift_on(date(day), &[]).unwrap().status;
+
+        assert_eq!(status("2026-10-05"), ShiftStatus::ON);
+        assert_eq!(status("2026-10-06"), ShiftStatus::ON);
+        assert_eq!(status("2026-10-07"), ShiftStatus::OFF);
+        assert_eq!(status("2026-10-09"), ShiftStatus::ON);
+        assert_eq!(status("2026-10-04"), ShiftStatus::OFF);
+        assert_eq!(status("2026-10-02"), ShiftStatus::ON);
+
+        let shift = job.get_scheduled_shift_on(date("2026-10-05"), &[]).unwrap();
+        assert_eq!(shift.start, Some(at("2026-10-05", "09:00")));
+        assert_eq!(shift.finish, Some(at("2026-10-05", "17:00")));
+        assert!(!shift.is_overridden);
+    }
+
+    #[test]
+    fn overrides_apply_without_a_pattern() {
+        let job = test_job();
+        let slot = ShiftSlot { start_time: NaiveTime::from_hms_opt(22, 0, 0).unwrap(), duration: TimeDelta::hours(8), label: Some("Nights".to_string()) };
+        let overrides = [schedule_override(1, "2026-10-12", ScheduleChange::ForceOn(Some(slot)))];
+
+        assert!(job.get_scheduled_shift_on(date("2026-10-13"), &overrides).is_none());
+        let shift = job.get_scheduled_shift_on(date("2026-10-12"), &overrides).unwrap();
+        assert_eq!(shift.status, ShiftStatus::ON);
+        assert_eq!(shift.finish, Some(at("2026-10-13", "06:00")));
+        assert_eq!(shift.label.as_deref(), Some("Nights"));
+        assert!(shift.is_overridden);
+    }
+
+    #[test]
+    fn swapped_days_trade_places_until_overridden_again() {
+        let job = rotating_job();
+        let status = |day: &str, overrides: &[ScheduleOverride]| job.get_scheduled_shift_on(date(day), overrides).unwrap().status;
+
+        let mut overrides = vec![schedule_override(1, "2026-10-06", ScheduleChange::SwapWith(date("2026-10-07")))];
+        assert_eq!(status("2026-10-06", &overrides), ShiftStatus::OFF);
+        assert_eq!(status("2026-10-07", &overrides), ShiftStatus::ON);
+
+        // The later change wins on both sides of the swap.
+        overrides.push(schedule_override(2, "2026-10-06", ScheduleChange::ForceOn(None)));
+        assert_eq!(status("2026-10-06", &overrides), ShiftStatus::ON);
+        assert_eq!(status("2026-10-07", &overrides), ShiftStatus::OFF);
+        assert!(!job.get_scheduled_shift_on(date("2026-10-07"), &overrides).unwrap().is_overridden);
+
+        let overrides = [schedule_override(1, "2026-10-05", ScheduleChange::ForceOff)];
+        assert_eq!(status("2026-10-05", &overrides), ShiftStatus::OFF);
+    }
+
+    #[test]
+    fn drafts_are_not_paid() {
+        let db = test_db();
+        let job = test_job();
+        shift(1, "2026-10-12", "09:00", "17:00").save(&db).unwrap();
+        let before = summary(&db, &job, "2026-10-12", "2026-10-18").get_earnings();
+
+        let mut draft = shift(2, "2026-10-13", "09:00", "17:00");
+        draft.is_confirmed = false;
+        draft.save(&db).unwrap();
+
+        assert_eq!(before, 9_600);
+        assert_eq!(summary(&db, &job, "2026-10-12", "2026-10-18").get_earnings(), before);
+    }
+
+    #[test]
+    fn generating_drafts_again_changes_nothing() {
+        let db = test_db();
+        let id_gen = IdGenerator::new(&db).unwrap();
+        let job = rotating_job();
+        let worked = Shift::new(id_gen.next_shift_id(), 1, date("2026-10-05"), ShiftType::Scheduled, at("2026-10-05", "10:00"), at("2026-10-05", "18:00"))
+            .saved(&db)
+            .unwrap();
+        let (from, to) = (date("2026-10-05"), date("2026-10-11"));
+
+        let first = job.generate_draft_shifts(&db, &id_gen, from, to, &[]).unwrap();
+        let again = job.generate_draft_shifts(&db, &id_gen, from, to, &[]).unwrap();
+
+        let created: Vec<NaiveDate> = first.created.iter().map(|draft| draft.date).collect();
+        assert_eq!(created, ["2026-10-06", "2026-10-09", "2026-10-10"].map(date));
+        assert!(first.created.iter().all(|draft| !draft.is_confirmed));
+        assert_eq!(first.skipped_confirmed, 1);
+        assert!(again.created.is_empty() && again.updated.is_empty() && again.removed.is_empty());
+        assert_eq!(again.skipped_confirmed, 1);
+
+        let shifts = job.get_shifts_for_period_of(from, to, &db).unwrap();
+        assert_eq!(shifts.len(), 4);
+        assert!(shifts.contains(&worked));
+    }
+
+    #[test]
+    fn exported_events_keep_their_uid() {
+        let db = test_db();
+        let job = rotating_job();
+        let export = |db: &Database| {
+            IcsExport::for_jobs(db, &[&job], &HashMap::new(), &HashMap::new(), &[], date("2026-10-05"), date("2026-10-06"))
+                .unwrap()
+                .events
+                .into_iter()
+                .map(|event| event.uid)
+                .collect::<Vec<_>>()
+        };
+        let rota = export(&db);
+
+        // Logging the day replaces the rota's event, a second shift that day is a new one.
+        shift(1, "2026-10-05", "09:30", "13:00").save(&db).unwrap();
+        shift(2, "2026-10-05", "14:00", "17:30").save(&db).unwrap();
+        let logged = export(&db);
+
+        assert_eq!(rota, ["rota-1-20261005@wage-calculator", "rota-1-20261006@wage-calculator"]);
+        assert_eq!(logged, ["rota-1-20261005@wage-calculator", "rota-1-20261005-1@wage-calculator", "rota-1-20261006@wage-calculator"]);
+        assert_eq!(export(&db), logged);
+    }
+
+    #[test]
+    fn ics_events_are_read_in_uk_local_time() {
+        let contents = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:one\r\n\
+            SUMMARY:Early\\, till\r\n  2\r\n\
+            DTSTART;X-NOTE=\"a:b\";TZID=\"Europe/London\":20261012T090000\r\n\
+            DTEND;TZID=Europe/London:20261012T170000\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:two\r\n\
+            DTSTART:20260712T080000Z\r\n\
+            DTEND;TZID=UTC:20260712T160000\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:three\r\n\
+            DTSTART;TZID=America/New_York:20261013T090000\r\n\
+            DTEND;TZID=America/New_York:20261013T170000\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:four\r\n\
+            DTSTART;VALUE=DATE:20261014\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            UID:five\r\n\
+            STATUS:CANCELLED\r\n\
+            DTSTART:20261015T090000\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let events = IcsImportEvent::parse_all(contents);
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].summary, "Early, till 2");
+        assert_eq!(events[0].start, IcsTime::DateTime(at("2026-10-12", "09:00")));
+        assert_eq!(events[0].end, IcsTime::DateTime(at("2026-10-12", "17:00")));
+        assert_eq!(events[1].start, IcsTime::DateTime(at("2026-07-12", "09:00")));
+        assert_eq!(events[1].end, IcsTime::DateTime(at("2026-07-12", "17:00")));
+        assert!(events[1].skip_reason.is_none());
+        assert!(events[2].skip_reason.is_some());
+        assert_eq!(events[3].start, IcsTime::Date(date("2026-10-14")));
+        assert_eq!(events[3].end, IcsTime::Date(date("2026-10-15")));
+    }
+
+    #[test]
+    fn utc_is_moved_onto_british_summer_time() {
+        assert_eq!(uk_local_from_utc(at("2026-03-29", "00:59")), at("2026-03-29", "00:59"));
+        assert_eq!(uk_local_from_utc(at("2026-03-29", "01:00")), at("2026-03-29", "02:00"));
+        assert_eq!(uk_local_from_utc(at("2026-10-25", "00:59")), at("2026-10-25", "01:59"));
+        assert_eq!(uk_local_from_utc(at("2026-10-25", "01:00")), at("2026-10-25", "01:00"));
+    }
+
+    #[test]
+    fn ics_duration_is_used_when_there_is_no_dtend() {
+        let contents = "BEGIN:VEVENT\r\n\
+            DTSTART:20261012T220000\r\n\
+            DURATION:PT8H30M\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20261014\r\n\
+            DURATION:P2D\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20261016T090000\r\n\
+            END:VEVENT\r\n";
+
+        let events = IcsImportEvent::parse_all(contents);
+
+        assert_eq!(events[0].end, IcsTime::DateTime(at("2026-10-13", "06:30")));
+        assert!(events[0].skip_reason.is_none());
+        assert_eq!(events[1].end, IcsTime::Date(date("2026-10-16")));
+        assert!(events[2].skip_reason.is_some());
+        assert_eq!(IcsImportEvent::parse_duration("P1DT2H"), Some(TimeDelta::hours(26)));
+        assert_eq!(IcsImportEvent::parse_duration("-PT1H"), None);
+    }
+
+    #[test]
+    fn ics_changes_update_the_shift_in_place() {
+        let db = test_db();
+        let id_gen = IdGenerator::new(&db).unwrap();
+        let job = test_job();
+        let event = |finish: &str| format!("BEGIN:VEVENT\r\nUID:one\r\nDTSTART:20261012T090000\r\nDTEND:20261012T{finish}00\r\nEND:VEVENT\r\n");
+
+        IcsImportPreview::new(&db, &job, &event("1700"), &[], &[]).unwrap().import(&db, &id_gen).unwrap();
+        let mut imported = job.get_shifts_for_period_of(date("2026-10-12"), date("2026-10-12"), &db).unwrap().remove(0);
+        imported.unpaid_break = Some(ShiftBreak { start: None, duration: TimeDelta::minutes(45) });
+        imported.update(&db).unwrap();
+
+        let preview = IcsImportPreview::new(&db, &job, &event("1800"), &[], &[]).unwrap();
+        assert_eq!(preview.rows[0].status, ImportStatus::Changed(imported.id));
+        preview.import(&db, &id_gen).unwrap();
+
+        let shifts = job.get_shifts_for_period_of(date("2026-10-12"), date("2026-10-12"), &db).unwrap();
+        assert_eq!(shifts, vec![Shift { finish: at("2026-10-12", "18:00"), ..imported }]);
+    }
+
+    #[test]
+    fn split_shifts_over_the_gap_get_the_allowance() {
+        let db = test_db();
+        let job = Job {
+            split_shift_allowance: Some(SplitShiftAllowance { min_gap: TimeDelta::hours(2), amount: 500 }),
+            ..test_job()
+        };
+        let split = |first_finish: &str, second_start: &str| shift(1, "2026-10-12", "07:00", "21:00").with_segments(vec![
+            ShiftSegment { start: at("2026-10-12", "07:00"), finish: at("2026-10-12", first_finish) },
+            ShiftSegment { start: at("2026-10-12", second_start), finish: at("2026-10-12", "21:00") },
+        ]);
+        let pay = |shift: &Shift| ShiftPayment::new_for_shift(shift, &job, &db, Vec::new(), &BankHolidayChecker::new(Vec::new())).unwrap();
+
+        let payments = pay(&split("11:00", "16:00"));
+        assert_eq!(payments, vec![
+            payment(10_800, ShiftPaymentType::Basic, 9 * 3600),
+            payment(500, ShiftPaymentType::SplitShiftAllowance, 0),
+        ]);
+        assert_eq!(ShiftPayment::get_total(&pay(&split("14:00", "15:00"))), 15_600);
+    }
+
+    #[test]
+    fn easter_sunday_is_worked_out_from_the_year() {
+        assert_eq!(BankHolidayChecker::get_easter_sunday(2024), date("2024-03-31"));
+        assert_eq!(BankHolidayChecker::get_easter_sunday(2025), date("2025-04-20"));
+        assert_eq!(BankHolidayChecker::get_easter_sunday(2026), date("2026-04-05"));
+        assert_eq!(BankHolidayChecker::get_easter_sunday(2038), date("2038-04-25"));
+    }
+
+    #[test]
+    fn english_bank_holidays_move_off_the_weekend() {
+        let holidays = BankHolidayChecker::for_region(UKRegion::England, 2026);
+        let dates: Vec<NaiveDate> = holidays.holidays.iter().map(|holiday| holiday.date).collect();
+        assert_eq!(dates, [
+            "2026-01-01", "2026-04-03", "2026-04-06", "2026-05-04", "2026-05-25", "2026-08-31", "2026-12-25", "2026-12-28",
+        ].map(date));
+
+        // Christmas on a Saturday and Boxing Day on a Sunday.
+        let holidays = BankHolidayChecker::for_region(UKRegion::England, 2027);
+        assert!(!holidays.is_bank_holiday(date("2027-12-25")));
+        assert!(holidays.is_bank_holiday(date("2027-12-27")));
+        assert!(holidays.is_bank_holiday(date("2027-12-28")));
+    }
+
+    #[test]
+    fn scottish_bank_holidays_differ_from_english() {
+        let holidays = BankHolidayChecker::for_region(UKRegion::Scotland, 2026);
+        assert!(holidays.is_bank_holiday(date("2026-01-02")));
+        assert!(holidays.is_bank_holiday(date("2026-08-03")));
+        assert!(holidays.is_bank_holiday(date("2026-11-30")));
+        assert!(!holidays.is_bank_holiday(date("2026-04-06")));
+        assert!(!holidays.is_bank_holiday(date("2026-08-31")));
+    }
+
+    #[test]
+    fn saved_bank_holidays_are_merged_over_the_region() {
+        let holiday = |id, job_id, region, day: &str, is_observed| BankHoliday {
+            id,
+            job_id,
+            region,
+            date: date(day),
+            name: "Saved".to_string(),
+            is_observed,
+        };
+        let saved = [
+            holiday(1, None, None, "2026-12-24", true),
+            holiday(2, Some(1), None, "2026-04-06", false),
+            holiday(3, Some(2), None, "2026-06-01", true),
+            holiday(4, None, Some(UKRegion::Scotland), "2026-12-28", false),
+            holiday(5, Some(1), None, "2026-12-24", false),
+        ];
+
+        let first = BankHolidayChecker::for_job(&test_job(), 2026, &saved);
+        assert!(!first.is_bank_holiday(date("2026-12-24")));
+        assert!(!first.is_bank_holiday(date("2026-04-06")));
+        assert!(!first.is_bank_holiday(date("2026-06-01")));
+        assert!(first.is_bank_holiday(date("2026-12-28")));
+
+        let second = BankHolidayChecker::for_job(&Job { id: 2, ..test_job() }, 2026, &saved);
+        assert!(second.is_bank_holiday(date("2026-12-24")));
+        assert!(second.is_bank_holiday(date("2026-04-06")));
+        assert!(second.is_bank_holiday(date("2026-06-01")));
+    }
+
+    #[test]
+    fn gov_uk_import_saves_only_the_differences() {
+        let db = test_db();
+        let id_gen = IdGenerator::new(&db).unwrap();
+        let contents = r#"{"england-and-wales": {"division": "england-and-wales", "events": [
+            {"title": "New Year’s Day", "date": "2026-01-01", "notes": "", "bunting": true},
+            {"title": "Good Friday", "date": "2026-04-03", "notes": "", "bunting": false},
+            {"title": "Easter Monday", "date": "2026-04-06", "notes": "", "bunting": true},
+            {"title": "Early May bank holiday", "date": "2026-05-04", "notes": "", "bunting": true},
+            {"title": "Extra bank holiday", "date": "2026-06-08", "notes": "", "bunting": true},
+            {"title": "August bank holiday", "date": "2026-08-31", "notes": "", "bunting": true},
+            {"title": "Christmas Day", "date": "2026-12-25", "notes": "", "bunting": true},
+            {"title": "Boxing Day", "date": "2026-12-28", "notes": "Substitute day", "bunting": true}
+        ]}}"#;
+
+        let import = BankHolidayImport::from_gov_uk_json(&db, &id_gen, contents).unwrap();
+        let dates = |holidays: &[BankHoliday]| holidays.iter().map(|holiday| (holiday.region, holiday.date)).collect::<Vec<_>>();
+        let mut added = dates(&import.added);
+        added.sort_by_key(|(region, _)| *region == Some(UKRegion::Wales));
+        let mut removed = dates(&import.removed);
+        removed.sort_by_key(|(region, _)| *region == Some(UKRegion::Wales));
+
+        assert_eq!(added, [(Some(UKRegion::England), date("2026-06-08")), (Some(UKRegion::Wales), date("2026-06-08"))]);
+        assert_eq!(removed, [(Some(UKRegion::England), date("2026-05-25")), (Some(UKRegion::Wales), date("2026-05-25"))]);
+        assert!(import.renamed.iter().all(|(old, holiday)| old == "Summer bank holiday" && holiday.name == "August bank holiday"));
+        assert_eq!(import.renamed.len(), 2);
+
+        let again = BankHolidayImport::from_gov_uk_json(&db, &id_gen, contents).unwrap();
+        assert!(again.added.is_empty() && again.removed.is_empty() && again.renamed.is_empty());
+        assert_eq!(BankHoliday::load_all(&db).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn lieu_days_are_earned_on_bank_holidays() {
+        let db = test_db();
+        let job = Job {
+            lieu_policy: Some(LieuPolicy { for_worked_bank_holiday: true, for_bank_holiday_on_day_off: true }),
+            ..rotating_job()
+        };
+        // 4 and 25 May are days off on the rota, 31 August is worked.
+        shift(1, "2026-08-31", "09:00", "17:00").save(&db).unwrap();
+        Shift::new(2, 1, date("2026-09-03"), ShiftType::PaidLeave, at("2026-09-03", "09:00"), at("2026-09-03", "17:00"))
+            .with_lieu_day(true)
+            .save(&db)
+            .unwrap();
+
+        let balance = LieuBalance::new(&db, &job, date("2026-05-01"), date("2026-09-30"), date("2026-10-01"), &[]).unwrap();
+
+        let reasons: Vec<(NaiveDate, LieuReason)> = balance.accrued.iter().map(|accrual| (accrual.date, accrual.reason)).collect();
+        assert_eq!(reasons, [
+            (date("2026-05-04"), LieuReason::BankHolidayOnDayOff),
+            (date("2026-05-25"), LieuReason::BankHolidayOnDayOff),
+            (date("2026-08-31"), LieuReason::WorkedBankHoliday),
+        ]);
+        assert_eq!(balance.get_balance(), 2);
+    }
+
+    #[test]
+    fn premium_periods_run_across_midnight_between_dates() {
+        let db = test_db();
+        let job = test_job();
+        let christmas = SalaryMultiplier {
+            name: "Christmas".to_string(),
+            premium_period: Some(PremiumPeriod {
+                start_month: 12,
+                start_day: 24,
+                start_time: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+                end_month: 12,
+                end_day: 27,
+                end_time: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            }),
+            ..multiplier(1, 2000, ReocurrementSchedule::SpecificDates { dates: Vec::new() })
+        };
+        let premium = |shift: Shift| {
+            ShiftPayment::new_for_shift(&shift, &job, &db, vec![christmas.clone()], &BankHolidayChecker::new(Vec::new()))
+                .unwrap()
+                .into_iter()
+                .filter(|payment| payment.payment_type == ShiftPaymentType::PremiumPeriod("Christmas".to_string()))
+                .map(|payment| (payment.amount, payment.seconds))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(premium(shift(1, "2026-12-24", "14:00", "22:00")), [(4_800, 4 * 3600)]);
+        assert_eq!(premium(shift(1, "2026-12-25", "09:00", "17:00")), [(9_600, 8 * 3600)]);
+        assert_eq!(premium(shift(1, "2026-12-27", "02:00", "10:00")), [(4_800, 4 * 3600)]);
+        assert!(premium(shift(1, "2026-12-27", "06:00", "14:00")).is_empty());
+    }
+
+    #[test]
+    fn rotation_text_reads_slots() {
+        let runs = JobForm::parse_rotation("2 on, 2 off, 3 on 22:00-06:00 Late nights, 2 off").unwrap();
+        assert_eq!(runs.len(), 4);
+        assert_eq!(runs[0], RotationRun::on(2));
+        assert_eq!(runs[2], RotationRun::on(3).with_slot(ShiftSlot {
+            start_time: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            duration: TimeDelta::hours(8),
+            label: Some("Late nights".to_string()),
+        }));
+
+        assert!(JobForm::parse_rotation("2 off 09:00-17:00, 2 on").is_err());
+        assert!(JobForm::parse_rotation("2 off").is_err());
+        assert!(JobForm::parse_rotation("0 on").is_err());
+    }
+
+    #[test]
+    fn clock_out_without_rounding_keeps_the_exact_times() {
+        let db = test_db();
+        let id_gen = IdGenerator::new(&db).unwrap();
+        let job = test_job();
+
+        let shift = Shift::clock_in(&db, &id_gen, &job, at("2026-10-12", "09:03"), &[]).unwrap();
+        let shift = shift.clock_out(&job, at("2026-10-12", "17:01"), &db).unwrap();
+
+        assert_eq!(shift.start, at("2026-10-12", "09:03"));
+        assert_eq!(shift.finish, at("2026-10-12", "17:01"));
+        assert_eq!(shift.get_time_worked(), TimeDelta::minutes(7 * 60 + 58));
+        assert!(Shift::get_running(&db).unwrap().is_none());
+    }
+
+    #[test]
+    fn clock_out_rounds_to_the_job_rules() {
+        let db = test_db();
+        let id_gen = IdGenerator::new(&db).unwrap();
+        let rule = |direction| Some(RoundingRule { interval_minutes: 15, direction, grace_minutes: 0 });
+        let job = Job {
+            clock_rounding: Some(ClockRounding { clock_in: rule(RoundingDirection::Up), clock_out: rule(RoundingDirection::Down) }),
+            ..test_job()
+        };
+
+        let shift = Shift::clock_in(&db, &id_gen, &job, at("2026-10-12", "09:03"), &[]).unwrap();
+        let shift = shift.clock_out(&job, at("2026-10-12", "17:01"), &db).unwrap();
+
+        assert_eq!(shift.start, at("2026-10-12", "09:15"));
+        assert_eq!(shift.finish, at("2026-10-12", "17:00"));
+        assert_eq!(shift.raw_finish, Some(at("2026-10-12", "17:01")));
+    }
+
+    #[test]
+    fn clock_in_is_refused_during_another_shift() {
+        let db = test_db();
+        let id_gen = IdGenerator::new(&db).unwrap();
+        let job = test_job();
+        Shift::new(id_gen.next_shift_id(), 2, NaiveDate::from_ymd_opt(2026, 10, 12).unwrap(), ShiftType::Scheduled, at("2026-10-12", "08:00"), at("2026-10-12", "12:00"))
+            .saved(&db)
+            .unwrap();
+
+        assert!(Shift::clock_in(&db, &id_gen, &job, at("2026-10-12", "09:00"), &[]).is_err());
+        assert!(Shift::clock_in(&db, &id_gen, &job, at("2026-10-12", "12:30"), &[]).is_ok());
+    }
+
+    #[test]
+    fn preview_reprices_confirmed_shifts_with_the_edited_multiplier() {
+        let db = test_db();
+        let job = test_job();
+        shift(1, "2026-10-11", "09:00", "17:00").save(&db).unwrap();
+        shift(2, "2026-10-12", "09:00", "17:00").save(&db).unwrap();
+        let mut draft = shift(3, "2026-10-18", "09:00", "17:00");
+        draft.is_confirmed = false;
+        draft.save(&db).unwrap();
+        let holidays = BankHolidayChecker::new(Vec::new());
+
+        let rows = RepricedShift::for_period(&db, &job, Vec::new(), vec![multiplier(1, 1500, sundays())], &holidays, date("2026-10-01"), date("2026-10-31")).unwrap();
+
+        assert_eq!(rows, vec![
+            RepricedShift { description: shift(1, "2026-10-11", "09:00", "17:00").describe(), lines: vec![("Sunday".to_string(), 9_600, 14_400)], before: 9_600, after: 14_400 },
+            RepricedShift { description: shift(2, "2026-10-12", "09:00", "17:00").describe(), lines: vec![("Basic".to_string(), 9_600, 9_600)], before: 9_600, after: 9_600 },
+        ]);
+    }
+
+    #[test]
+    fn multipliers_are_read_to_the_nearest_thousandth() {
+        assert_eq!(Multiplier::from_floating_point(1.15).value, 1_150);
+        assert_eq!(Multiplier::from_floating_point(1.125).value, 1_125);
+        assert_eq!(Multiplier::from_floating_point(2.0).value, 2_000);
+    }
+
+    #[test]
+    fn jobs_saved_before_student_loans_are_migrated() {
+        let db = test_db();
+        let old = JobV1::from(test_job());
+        let rw = db.rw_transaction().unwrap();
+        rw.insert(old).unwrap();
+        rw.commit().unwrap();
+
+        migrate_models(&db).unwrap();
+
+        let jobs = Job::load_all(&db).unwrap();
+        assert_eq!(jobs.get(&1), Some(&test_job()));
+    }
+
+    #[test]
+    fn tax_and_national_insurance_on_annual_pay() {
+        let summary = TaxSummary::for_annual_amount(3_000_000, UKRegion::England);
+        assert_eq!(summary.get_tax_prediction(), 348_600);
+        assert_eq!(summary.get_national_insurance_prediction(), 139_440);
+
+        let summary = TaxSummary::for_annual_amount(6_000_000, UKRegion::England);
+        assert_eq!(summary.get_tax_prediction(), 1_143_200);
+        assert_eq!(summary.get_national_insurance_prediction(), 321_060);
+
+        let summary = TaxSummary::for_annual_amount(1_200_000, UKRegion::England);
+        assert_eq!(summary.get_tax_prediction(), 0);
+        assert_eq!(summary.get_national_insurance_prediction(), 0);
+    }
+
+    #[test]
+    fn student_loans_are_repaid_in_whole_pounds() {
+        // £3,000 a month against Plan 2's £2,448.75 monthly threshold.
+        assert_eq!(StudentLoanPlan::Plan2.get_repayment(300_000, 12), 4_900);
+        assert_eq!(StudentLoanPlan::Postgraduate.get_repayment(300_000, 12), 7_500);
+        assert_eq!(StudentLoanPlan::Plan2.get_repayment(200_000, 12), 0);
+    }
+
+    #[test]
+    fn pension_on_qualifying_earnings_skips_the_lower_band() {
+        let pension = |is_on_qualifying_earnings| Pension { contribution: 50, method: PensionMethod::NetPay, is_on_qualifying_earnings };
+        assert_eq!(pension(true).get_contribution(300_000, 12), 12_400);
+        assert_eq!(pension(false).get_contribution(300_000, 12), 15_000);
+    }
+
+    #[test]
+    fn dashboard_periods_stop_at_the_tax_year() {
+        let db = test_db();
+        let job = test_job();
+        // Tax weeks start on Sunday, so the first and last weeks of 2026/2027 straddle the year.
+        for (id, day) in [(1, "2026-04-05"), (2, "2026-04-06"), (3, "2027-04-05"), (4, "2027-04-06")] {
+            shift(id, day, "09:00", "17:00").save(&db).unwrap();
+        }
+
+        let dashboard = EarningsDashboard::new(&db, &[&job], &HashMap::new(), &[], PayslipPeriod::Weekly, 2026, date("2027-10-18")).unwrap();
+
+        let gross: Vec<u32> = dashboard.periods.iter().map(|earnings| earnings.totals.gross).collect();
+        assert_eq!((gross[0], gross[gross.len() - 1]), (9_600, 9_600));
+        assert_eq!(gross.iter().sum::<u32>(), 19_200);
+        assert_eq!(dashboard.last_year.iter().map(|earnings| earnings.totals.gross).sum::<u32>(), 9_600);
+    }
+}